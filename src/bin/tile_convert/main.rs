@@ -0,0 +1,256 @@
+// Converts the RON .ground and .weather assets into the tiled binary format of world/tiles.rs.
+//
+//   cargo run --release --bin tile_convert -- --tile-size 1.0 europe_ron.ground assets/world/europe.ground
+//
+// The kind follows the input extension. Ground tiles are read one at a time as the aircraft
+// moves, weather tiles are stitched back together at load. The .weather loader still takes
+// RON, the .ground loader only takes tiles.
+
+use std::{ops::Range, path::PathBuf, process::ExitCode};
+
+use serde::Deserialize;
+use thiserror::Error;
+
+#[path = "../../world/ground/format.rs"]
+mod ground_format;
+#[path = "../../world/tiles.rs"]
+mod tiles;
+#[path = "../../projectile/weather/data.rs"]
+mod weather_data;
+#[path = "../../projectile/weather/tiles.rs"]
+mod weather_tiles;
+
+// the shared files use the game's module paths
+mod world {
+    pub(crate) use crate::tiles;
+}
+
+mod projectile {
+    pub mod weather {
+        pub(crate) use crate::weather_data::WeatherData;
+    }
+}
+
+use ground_format::{GroundTile, LandCover};
+use tiles::{TileError, TileHeader, TileKind};
+use weather_data::{WeatherData, WeatherGridError};
+
+const DEFAULT_TILE_SIZE: f64 = 1.0; // degrees
+
+#[derive(Debug, Error)]
+pub enum ConvertError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("{0}")]
+    Usage(String),
+
+    #[error("Failed to parse RON: {0}")]
+    Ron(#[from] ron::de::SpannedError),
+
+    #[error("`{0}` is neither .ground nor .weather")]
+    UnknownKind(PathBuf),
+
+    #[error("Inconsistent ground grid: {0}")]
+    Ground(&'static str),
+
+    #[error("Inconsistent weather grid: {0}")]
+    Grid(#[from] WeatherGridError),
+
+    #[error("Written tiles do not read back: {0}")]
+    Tiles(#[from] TileError),
+}
+
+// --- Ground ---
+
+// the dense RON grid the game used to load whole
+#[derive(Deserialize)]
+struct GroundData {
+    lats: Vec<f64>,
+    lons: Vec<f64>,
+    height: Vec<f32>, // [lat][lon]
+    land_use: Vec<LandCover>,
+}
+
+// grid points inside the tile edges plus one on either side
+fn span(axis: &[f64], (low, high): (f64, f64)) -> Range<usize> {
+    let start: usize = axis.partition_point(|value| *value < low).saturating_sub(1);
+    let end: usize = (axis.partition_point(|value| *value <= high) + 1).min(axis.len());
+
+    start..end.max((start + 2).min(axis.len()))
+}
+
+fn convert_ground(bytes: &[u8], tile_size: f64) -> Result<Vec<u8>, ConvertError> {
+    let data: GroundData = ron::de::from_bytes(bytes)?;
+
+    let (n_lat, n_lon) = (data.lats.len(), data.lons.len());
+    let ascending = |axis: &[f64]| axis.windows(2).all(|pair| pair[0] < pair[1]);
+
+    if n_lat < 2 || n_lon < 2 {
+        return Err(ConvertError::Ground("needs at least 2x2 points"));
+    }
+    if !ascending(&data.lats) || !ascending(&data.lons) {
+        return Err(ConvertError::Ground("axes must be strictly ascending"));
+    }
+    if data.height.len() != n_lat * n_lon || data.land_use.len() != n_lat * n_lon {
+        return Err(ConvertError::Ground(
+            "height and land_use must be lats x lons",
+        ));
+    }
+
+    let header = TileHeader {
+        kind: TileKind::Ground,
+        lat_range: (data.lats[0], data.lats[n_lat - 1]),
+        lon_range: (data.lons[0], data.lons[n_lon - 1]),
+        tile_size,
+        entries: Vec::new(),
+        extra: Vec::new(),
+    };
+
+    let (n_lat_tiles, n_lon_tiles) = header.tile_count();
+    let edges = |min: f64, index: i32| {
+        (
+            min + index as f64 * tile_size,
+            min + (index + 1) as f64 * tile_size,
+        )
+    };
+
+    let mut tiles: Vec<((i32, i32), Vec<u8>)> = Vec::new();
+
+    for lat_tile in 0..n_lat_tiles {
+        let rows: Range<usize> = span(&data.lats, edges(header.lat_range.0, lat_tile));
+
+        for lon_tile in 0..n_lon_tiles {
+            let columns: Range<usize> = span(&data.lons, edges(header.lon_range.0, lon_tile));
+            let cells = || {
+                rows.clone()
+                    .flat_map(|i| (i * n_lon + columns.start)..(i * n_lon + columns.end))
+            };
+
+            let tile = GroundTile {
+                lats: data.lats[rows.clone()].to_vec(),
+                lons: data.lons[columns.clone()].to_vec(),
+                height: cells().map(|cell| data.height[cell]).collect(),
+                land_use: cells().map(|cell| data.land_use[cell]).collect(),
+            };
+
+            tiles.push(((lat_tile, lon_tile), tile.encode()));
+        }
+    }
+
+    Ok(header.encode(tiles))
+}
+
+// --- Weather ---
+
+fn convert_weather(bytes: &[u8], tile_size: f64) -> Result<Vec<u8>, ConvertError> {
+    let data: WeatherData = ron::de::from_bytes(bytes)?;
+    data.validate()?;
+
+    Ok(data.to_tiles(tile_size))
+}
+
+// --- Output ---
+
+// read the output back the way the game does
+fn verify(bytes: &[u8], kind: TileKind) -> Result<usize, ConvertError> {
+    match kind {
+        TileKind::Ground => {
+            let header: TileHeader = TileHeader::decode(bytes, kind)?;
+            let (n_lat_tiles, n_lon_tiles) = header.tile_count();
+
+            for lat_tile in 0..n_lat_tiles {
+                for lon_tile in 0..n_lon_tiles {
+                    let entry = header
+                        .entry((lat_tile, lon_tile))
+                        .ok_or(TileError::Invalid("tile missing from the index"))?;
+
+                    GroundTile::decode(header.payload(bytes, entry)?)?;
+                }
+            }
+
+            Ok(header.entries.len())
+        }
+        TileKind::Weather => {
+            WeatherData::from_tiles(bytes)?.validate()?;
+
+            Ok(TileHeader::decode(bytes, kind)?.entries.len())
+        }
+    }
+}
+
+const USAGE: &str = "usage: tile_convert [--tile-size DEGREES] INPUT OUTPUT";
+
+fn run() -> Result<(), ConvertError> {
+    let usage = |message: &str| ConvertError::Usage(format!("{message}\n{USAGE}"));
+
+    let mut tile_size: f64 = DEFAULT_TILE_SIZE;
+    let mut paths: Vec<PathBuf> = Vec::new();
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--tile-size" => {
+                tile_size = args
+                    .next()
+                    .and_then(|value| value.parse::<f64>().ok())
+                    .filter(|size| *size > 0.)
+                    .ok_or_else(|| usage("--tile-size takes a positive number of degrees"))?
+            }
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return Ok(());
+            }
+            _ if arg.starts_with('-') => return Err(usage(&format!("unknown option {arg}"))),
+            _ => paths.push(PathBuf::from(arg)),
+        }
+    }
+
+    let [input, output] = &paths[..] else {
+        return Err(usage("expected an input and an output file"));
+    };
+    if input == output {
+        return Err(usage("the output would overwrite the input"));
+    }
+
+    let kind: TileKind = match input.extension().and_then(|extension| extension.to_str()) {
+        Some("ground") => TileKind::Ground,
+        Some("weather") => TileKind::Weather,
+        _ => return Err(ConvertError::UnknownKind(input.clone())),
+    };
+
+    eprintln!("reading {}", input.display());
+    let bytes: Vec<u8> = std::fs::read(input)?;
+
+    let tiled: Vec<u8> = match kind {
+        TileKind::Ground => convert_ground(&bytes, tile_size)?,
+        TileKind::Weather => convert_weather(&bytes, tile_size)?,
+    };
+
+    let n_tiles: usize = verify(&tiled, kind)?;
+
+    if let Some(parent) = output.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(output, &tiled)?;
+
+    eprintln!(
+        "wrote {}, {} tiles, {:.1} MB from {:.1} MB of RON",
+        output.display(),
+        n_tiles,
+        tiled.len() as f64 / 1e6,
+        bytes.len() as f64 / 1e6
+    );
+
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("error: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,274 @@
+// GRIB2 through the `grib` crate, regular lat/lon grids with simple or complex packing
+use std::{
+    fs::File,
+    io::{BufReader, Read, Seek},
+    path::Path,
+};
+
+use grib::{Code::Name, Grib2SubmessageDecoder, codetables::grib2::Table4_4};
+
+use crate::{ConvertError, Field, GRAVITY, Selection, Slice, unix_seconds};
+
+// fixed surface types, WMO code table 4.5
+const ISOBARIC: u8 = 100;
+const MEAN_SEA_LEVEL: u8 = 101;
+const HEIGHT_ABOVE_GROUND: u8 = 103;
+
+// (discipline, category, number) and the first fixed surface to a field, with a unit scale
+fn parameter_field(parameter: (u8, u8, u8), surface: u8, height: f64) -> Option<(Field, f32)> {
+    let at = |metres: f64| surface == HEIGHT_ABOVE_GROUND && (height - metres).abs() < 0.5;
+
+    let field = match parameter {
+        (0, 0, 0) if at(2.0) => (Field::Temperature2m, 1.0),
+        (0, 0, 6) if at(2.0) => (Field::DewPoint2m, 1.0),
+        (0, 1, 1) if at(2.0) => (Field::RelativeHumidity2m, 1.0),
+        (0, 3, 1) if surface == MEAN_SEA_LEVEL => (Field::PressureMsl, 1.0),
+        (0, 2, 2) if at(10.0) => (Field::U10, 1.0),
+        (0, 2, 3) if at(10.0) => (Field::V10, 1.0),
+        (0, 2, 2) if at(100.0) => (Field::U100, 1.0),
+        (0, 2, 3) if at(100.0) => (Field::V100, 1.0),
+        (0, 6, 3) => (Field::CloudLow, 0.01), // %
+        (0, 6, 4) => (Field::CloudMid, 0.01),
+        (0, 6, 5) => (Field::CloudHigh, 0.01),
+        (0, 2, 2) if surface == ISOBARIC => (Field::LevelU, 1.0),
+        (0, 2, 3) if surface == ISOBARIC => (Field::LevelV, 1.0),
+        (0, 0, 0) if surface == ISOBARIC => (Field::LevelTemperature, 1.0),
+        (0, 3, 4) if surface == ISOBARIC => (Field::LevelGeopotential, 1.0),
+        (0, 3, 5) if surface == ISOBARIC => (Field::LevelGeopotential, GRAVITY), // gpm
+        _ => return None,
+    };
+
+    Some(field)
+}
+
+pub fn read_slices(path: &Path, selection: &Selection) -> Result<Vec<Slice>, ConvertError> {
+    file_slices(BufReader::new(File::open(path)?), selection)
+}
+
+fn file_slices<R: Read + Seek>(
+    reader: R,
+    selection: &Selection,
+) -> Result<Vec<Slice>, ConvertError> {
+    let grib2 = grib::from_reader(reader)?;
+
+    // a message cut short in section 0 reads as an empty file
+    if grib2.iter().next().is_none() {
+        return Err(ConvertError::NoGribMessages);
+    }
+
+    let mut slices: Vec<Slice> = Vec::new();
+
+    for (_, submessage) in grib2.iter() {
+        let prod_def = submessage.prod_def();
+
+        let (Some(category), Some(number), Some((surface, _))) = (
+            prod_def.parameter_category(),
+            prod_def.parameter_number(),
+            prod_def.fixed_surfaces(),
+        ) else {
+            continue;
+        };
+
+        let discipline: u8 = submessage.indicator().discipline;
+        let Some((field, scale)) = parameter_field(
+            (discipline, category, number),
+            surface.surface_type,
+            surface.value(),
+        ) else {
+            continue;
+        };
+
+        // --- Valid time ---
+        let info = submessage.temporal_raw_info();
+        let reference = &info.ref_time_unchecked;
+        let lead: f64 = match &info.forecast_time_diff {
+            None => 0.,
+            Some(forecast) => {
+                let unit: f64 = match forecast.unit {
+                    Name(Table4_4::Second) => 1.0,
+                    Name(Table4_4::Minute) => 60.0,
+                    Name(Table4_4::Hour) => 3_600.0,
+                    Name(Table4_4::ThreeHours) => 10_800.0,
+                    Name(Table4_4::SixHours) => 21_600.0,
+                    Name(Table4_4::TwelveHours) => 43_200.0,
+                    Name(Table4_4::Day) => 86_400.0,
+                    _ => continue,
+                };
+                unit * forecast.value as f64
+            }
+        };
+
+        let time: f64 = unix_seconds(
+            reference.year.into(),
+            reference.month.into(),
+            reference.day.into(),
+            reference.hour.into(),
+            reference.minute.into(),
+            reference.second.into(),
+        ) + lead;
+
+        if !selection.contains_time(time) {
+            continue;
+        }
+
+        // isobaric surfaces are given in Pa
+        let level: Option<f32> = field.on_levels().then(|| (surface.value() / 100.0) as f32);
+
+        // --- Grid ---
+        let (n_i, n_j) = submessage.grid_shape()?;
+        let points: Vec<((usize, usize), (f32, f32))> =
+            submessage.ij()?.zip(submessage.latlons()?).collect();
+
+        let values: Vec<f32> = Grib2SubmessageDecoder::from(submessage)?
+            .dispatch()?
+            .collect();
+
+        // the grid shape is only trusted once the decoded values agree with it
+        if n_i.checked_mul(n_j) != Some(values.len()) || points.len() != values.len() {
+            return Err(ConvertError::GribGrid {
+                field: field.name(),
+                n_i,
+                n_j,
+                found: values.len(),
+            });
+        }
+
+        let mut lats: Vec<f32> = vec![0.; n_j];
+        let mut lons: Vec<f32> = vec![0.; n_i];
+        let mut grid: Vec<f32> = vec![f32::NAN; n_i * n_j];
+
+        for (((i, j), (lat, lon)), value) in points.into_iter().zip(values) {
+            lats[j] = lat;
+            lons[i] = lon;
+            grid[j * n_i + i] = value * scale;
+        }
+
+        slices.push(selection.crop(Slice {
+            field,
+            level,
+            time,
+            lats,
+            lons,
+            values: grid,
+        })?);
+    }
+
+    Ok(slices)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::parse_date_time;
+
+    fn section(number: u8, body: &[u8]) -> Vec<u8> {
+        let mut out: Vec<u8> = ((body.len() + 5) as u32).to_be_bytes().to_vec();
+        out.push(number);
+        out.extend(body);
+        out
+    }
+
+    // 2 m temperature on a 2 x 2 grid scanned north to south, simple packing, 8 bits per value
+    fn message(n_points: u32, packed: &[u8]) -> Vec<u8> {
+        let micro = |degrees: u32| (degrees * 1_000_000).to_be_bytes();
+
+        // --- Identification, 2024-06-01 06:00 ---
+        let mut identification: Vec<u8> = vec![0, 98, 0, 0, 2, 0, 1];
+        identification.extend(2024u16.to_be_bytes());
+        identification.extend([6, 1, 6, 0, 0, 0, 1]);
+
+        // --- Grid, template 3.0 ---
+        let mut grid: Vec<u8> = vec![0];
+        grid.extend(n_points.to_be_bytes());
+        grid.extend([0, 0, 0, 0]);
+        grid.extend([6, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]); // earth
+        grid.extend(2u32.to_be_bytes()); // Ni
+        grid.extend(2u32.to_be_bytes()); // Nj
+        grid.extend([0; 8]);
+        grid.extend(micro(49));
+        grid.extend(micro(7));
+        grid.push(0x30);
+        grid.extend(micro(48));
+        grid.extend(micro(8));
+        grid.extend(micro(1));
+        grid.extend(micro(1));
+        grid.push(0); // +i, -j
+
+        // --- Product, template 4.0, analysis at 2 m ---
+        let mut product: Vec<u8> = vec![0, 0, 0, 0];
+        product.extend([0, 0, 2, 0, 0, 0, 0, 0, 1]);
+        product.extend(0u32.to_be_bytes());
+        product.extend([103, 0]);
+        product.extend(2u32.to_be_bytes());
+        product.extend([255, 0, 0, 0, 0, 0]);
+
+        // --- Data representation, template 5.0: 280 K + packed ---
+        let mut representation: Vec<u8> = n_points.to_be_bytes().to_vec();
+        representation.extend([0, 0]);
+        representation.extend(280f32.to_be_bytes());
+        representation.extend([0, 0, 0, 0, 8, 0]);
+
+        let sections: Vec<u8> = [
+            section(1, &identification),
+            section(3, &grid),
+            section(4, &product),
+            section(5, &representation),
+            section(6, &[255]),
+            section(7, packed),
+        ]
+        .concat();
+
+        let mut out: Vec<u8> = b"GRIB\0\0\0\x02".to_vec();
+        out.extend(((16 + sections.len() + 4) as u64).to_be_bytes());
+        out.extend(sections);
+        out.extend(b"7777");
+        out
+    }
+
+    fn selection() -> Selection {
+        Selection {
+            lat: (-90.0, 90.0),
+            lon: (-180.0, 180.0),
+            start: f64::NEG_INFINITY,
+            end: f64::INFINITY,
+        }
+    }
+
+    fn slices(bytes: Vec<u8>) -> Result<Vec<Slice>, ConvertError> {
+        file_slices(Cursor::new(bytes), &selection())
+    }
+
+    #[test]
+    fn reads_a_simple_packed_field() {
+        let slices: Vec<Slice> = slices(message(4, &[0, 1, 2, 3])).unwrap();
+
+        let [slice] = &slices[..] else {
+            panic!("expected one slice, found {}", slices.len());
+        };
+        assert_eq!(slice.field, Field::Temperature2m);
+        assert_eq!(slice.level, None);
+        assert_eq!(slice.time, parse_date_time("2024-06-01T06:00").unwrap());
+        assert_eq!(slice.lats, vec![48.0, 49.0]);
+        assert_eq!(slice.lons, vec![7.0, 8.0]);
+
+        // south row first once cropped
+        assert_eq!(slice.values, vec![282.0, 283.0, 280.0, 281.0]);
+    }
+
+    #[test]
+    fn truncated_message_is_an_error() {
+        let bytes: Vec<u8> = message(4, &[0, 1, 2, 3]);
+
+        for len in [10, 40, bytes.len() - 5] {
+            assert!(slices(bytes[..len].to_vec()).is_err());
+        }
+    }
+
+    #[test]
+    fn values_not_matching_the_grid_are_an_error() {
+        // 4 grid points with only 3 packed values
+        assert!(slices(message(3, &[0, 1, 2])).is_err());
+    }
+}
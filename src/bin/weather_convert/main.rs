@@ -0,0 +1,740 @@
+// Converts reanalysis / forecast files into the .weather assets read by WeatherDataLoader.
+//
+//   cargo run --release --features weather_convert --bin weather_convert -- \
+//       --bbox 47.5,49.5,6.5,9.0 --start 2024-06-01T06:00 --end 2024-06-01T18:00 \
+//       --output assets/weather/data.weather era5_single.nc era5_levels.nc
+//
+// Inputs are NetCDF classic (CDF-1, CDF-2, CDF-5) or GRIB2 and are told apart by their
+// magic bytes. NetCDF-4 / HDF5 downloads have to go through `nccopy -k cdf5` first.
+// ERA5 and ICON short names are understood, see `netcdf::FIELDS` and `grib::parameter_field`.
+
+use std::{collections::BTreeMap, fs::File, io::Read, path::PathBuf, process::ExitCode};
+
+use thiserror::Error;
+
+#[path = "../../projectile/weather/data.rs"]
+mod data;
+mod grib;
+mod netcdf;
+
+use data::{WeatherData, WeatherGridError};
+
+const GRAVITY: f32 = 9.80665; // m/s^2
+
+#[derive(Debug, Error)]
+pub enum ConvertError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("{0}")]
+    Usage(String),
+
+    #[error("NetCDF error: {0}")]
+    NetCdf(#[from] netcdf::NetCdfError),
+
+    #[error("GRIB2 error: {0}")]
+    Grib(#[from] ::grib::GribError),
+
+    #[error("No complete GRIB2 message found")]
+    NoGribMessages,
+
+    #[error("GRIB2 `{field}` has {found} values for a {n_i} x {n_j} grid")]
+    GribGrid {
+        field: &'static str,
+        n_i: usize,
+        n_j: usize,
+        found: usize,
+    },
+
+    #[error("`{0}` is NetCDF-4 / HDF5, convert it with `nccopy -k cdf5` first")]
+    Hdf5(PathBuf),
+
+    #[error("`{0}` is neither NetCDF classic nor GRIB2")]
+    UnknownFormat(PathBuf),
+
+    #[error("No grid points of `{field}` inside the bounding box")]
+    EmptySelection { field: &'static str },
+
+    #[error("`{field}` is on a different grid than `{reference}`")]
+    InconsistentGrid {
+        field: &'static str,
+        reference: &'static str,
+    },
+
+    #[error("`{field}` has no data for {time}{level}")]
+    MissingSlice {
+        field: &'static str,
+        time: String,
+        level: String,
+    },
+
+    #[error("Required field `{0}` was not found in any input")]
+    MissingField(&'static str),
+
+    #[error("{0}")]
+    Grid(#[from] WeatherGridError),
+
+    #[error("Failed to write RON: {0}")]
+    Ron(#[from] ron::Error),
+}
+
+// --- Fields ---
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Field {
+    Temperature2m,      // K
+    DewPoint2m,         // K
+    RelativeHumidity2m, // %
+    PressureMsl,        // Pa
+    U10,
+    V10,
+    U100,
+    V100,
+    CloudLow, // 0..1
+    CloudMid,
+    CloudHigh,
+    LevelU,
+    LevelV,
+    LevelTemperature,  // K
+    LevelGeopotential, // m^2/s^2
+}
+
+impl Field {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Field::Temperature2m => "temperature_2m",
+            Field::DewPoint2m => "dew_point_2m",
+            Field::RelativeHumidity2m => "relative_humidity_2m",
+            Field::PressureMsl => "pressure_msl",
+            Field::U10 => "u10",
+            Field::V10 => "v10",
+            Field::U100 => "u100",
+            Field::V100 => "v100",
+            Field::CloudLow => "cloud_low",
+            Field::CloudMid => "cloud_mid",
+            Field::CloudHigh => "cloud_high",
+            Field::LevelU => "level_u",
+            Field::LevelV => "level_v",
+            Field::LevelTemperature => "level_temperature",
+            Field::LevelGeopotential => "level_geopotential",
+        }
+    }
+
+    pub fn on_levels(&self) -> bool {
+        matches!(
+            self,
+            Field::LevelU | Field::LevelV | Field::LevelTemperature | Field::LevelGeopotential
+        )
+    }
+}
+
+// one horizontal field at one time (and level), ascending once cropped
+#[derive(Debug)]
+pub struct Slice {
+    pub field: Field,
+    pub level: Option<f32>, // hPa
+    pub time: f64,          // s since the unix epoch
+    pub lats: Vec<f32>,
+    pub lons: Vec<f32>,
+    pub values: Vec<f32>, // [lat][lon]
+}
+
+// --- Selection ---
+
+#[derive(Debug, Clone, Copy)]
+pub struct Selection {
+    pub lat: (f32, f32),
+    pub lon: (f32, f32), // -180..180
+    pub start: f64,
+    pub end: f64,
+}
+
+impl Selection {
+    pub fn contains_time(&self, time: f64) -> bool {
+        time >= self.start && time <= self.end
+    }
+
+    // indices of the axis inside (lo, hi) plus one neighbour either side, ascending
+    fn axis(values: &[f32], (lo, hi): (f32, f32)) -> Vec<(usize, f32)> {
+        let mut sorted: Vec<(usize, f32)> = values.iter().copied().enumerate().collect();
+        sorted.sort_by(|a, b| a.1.total_cmp(&b.1));
+        sorted.dedup_by(|a, b| (a.1 - b.1).abs() < 1e-4);
+
+        let first: usize = sorted.partition_point(|(_, v)| *v < lo).saturating_sub(1);
+        let last: usize = (sorted.partition_point(|(_, v)| *v <= hi) + 1).min(sorted.len());
+
+        match first < last {
+            true => sorted[first..last].to_vec(),
+            false => Vec::new(),
+        }
+    }
+
+    // crops a [lat][lon] slice given in any axis order, longitudes may be 0..360
+    pub fn crop(&self, slice: Slice) -> Result<Slice, ConvertError> {
+        let wrapped: Vec<f32> = slice
+            .lons
+            .iter()
+            .map(|lon| match *lon > 180.0 {
+                true => lon - 360.0,
+                false => *lon,
+            })
+            .collect();
+
+        let lat_idx = Self::axis(&slice.lats, self.lat);
+        let lon_idx = Self::axis(&wrapped, self.lon);
+
+        if lat_idx.len() < 2 || lon_idx.len() < 2 {
+            return Err(ConvertError::EmptySelection {
+                field: slice.field.name(),
+            });
+        }
+
+        let (n_lon, source) = (slice.lons.len(), &slice.values);
+        let values: Vec<f32> = lat_idx
+            .iter()
+            .flat_map(|(i, _)| lon_idx.iter().map(move |(j, _)| source[i * n_lon + j]))
+            .collect();
+
+        Ok(Slice {
+            lats: lat_idx.iter().map(|(_, lat)| *lat).collect(),
+            lons: lon_idx.iter().map(|(_, lon)| *lon).collect(),
+            values,
+            ..slice
+        })
+    }
+}
+
+// --- Time ---
+
+// days since 1970-01-01 of a proleptic Gregorian date
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year: i64 = if month <= 2 { year - 1 } else { year };
+    let era: i64 = year.div_euclid(400);
+    let year_of_era: i64 = year - era * 400;
+    let day_of_year: i64 = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let day_of_era: i64 = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146_097 + day_of_era - 719_468
+}
+
+pub fn unix_seconds(year: i64, month: i64, day: i64, hour: i64, minute: i64, second: f64) -> f64 {
+    (days_from_civil(year, month, day) * 86_400 + hour * 3_600 + minute * 60) as f64 + second
+}
+
+// YYYY-MM-DD with an optional [T ]HH[:MM[:SS]], always UTC
+pub fn parse_date_time(text: &str) -> Option<f64> {
+    let text: &str = text.trim().trim_end_matches('Z');
+    let (date, time) = match text.split_once(['T', ' ']) {
+        Some((date, time)) => (date, time.trim()),
+        None => (text, ""),
+    };
+
+    let mut date = date.splitn(3, '-').map(|part| part.parse::<i64>());
+    let (year, month, day) = (date.next()?.ok()?, date.next()?.ok()?, date.next()?.ok()?);
+
+    let mut time = time.split(':').filter(|part| !part.is_empty());
+    let hour: i64 = time.next().map_or(Some(0), |part| part.parse().ok())?;
+    let minute: i64 = time.next().map_or(Some(0), |part| part.parse().ok())?;
+    let second: f64 = time.next().map_or(Some(0.), |part| part.parse().ok())?;
+
+    Some(unix_seconds(year, month, day, hour, minute, second))
+}
+
+fn format_time(time: f64) -> String {
+    let days: i64 = (time / 86_400.0).floor() as i64;
+    let seconds: i64 = time as i64 - days * 86_400;
+
+    // civil_from_days
+    let z: i64 = days + 719_468;
+    let era: i64 = z.div_euclid(146_097);
+    let day_of_era: i64 = z - era * 146_097;
+    let year_of_era: i64 =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year: i64 = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp: i64 = (5 * day_of_year + 2) / 153;
+    let day: i64 = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month: i64 = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year: i64 = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}Z",
+        seconds / 3_600,
+        seconds % 3_600 / 60
+    )
+}
+
+// --- Assembly ---
+
+fn relative_humidity(temperature: f32, dew_point: f32) -> f32 {
+    // Magnus over water, both in K
+    let vapor_pressure = |t: f32| {
+        let celsius: f32 = t - 273.15;
+        (17.27 * celsius / (celsius + 237.3)).exp()
+    };
+
+    (100.0 * vapor_pressure(dew_point) / vapor_pressure(temperature)).clamp(0.0, 100.0)
+}
+
+struct Grid {
+    reference: &'static str,
+    lats: Vec<f32>,
+    lons: Vec<f32>,
+}
+
+impl Grid {
+    fn check(&self, slice: &Slice) -> Result<(), ConvertError> {
+        let same = |a: &[f32], b: &[f32]| {
+            a.len() == b.len() && a.iter().zip(b).all(|(a, b)| (a - b).abs() < 1e-4)
+        };
+
+        match same(&self.lats, &slice.lats) && same(&self.lons, &slice.lons) {
+            true => Ok(()),
+            false => Err(ConvertError::InconsistentGrid {
+                field: slice.field.name(),
+                reference: self.reference,
+            }),
+        }
+    }
+}
+
+type Slices = BTreeMap<(Field, i64, u64), Vec<f32>>; // (field, level Pa, time bits)
+
+fn assemble(slices: Vec<Slice>) -> Result<WeatherData, ConvertError> {
+    let Some(first) = slices
+        .iter()
+        .find(|slice| slice.field == Field::Temperature2m)
+    else {
+        return Err(ConvertError::MissingField(Field::Temperature2m.name()));
+    };
+
+    let grid = Grid {
+        reference: first.field.name(),
+        lats: first.lats.clone(),
+        lons: first.lons.clone(),
+    };
+
+    let mut times: Vec<f64> = Vec::new();
+    let mut levels: Vec<f32> = Vec::new();
+    let mut table: Slices = BTreeMap::new();
+
+    for slice in slices {
+        grid.check(&slice)?;
+
+        match slice.level {
+            Some(level) => levels.push(level),
+            None => times.push(slice.time),
+        }
+
+        let level: i64 = slice
+            .level
+            .map_or(-1, |level| (level * 100.0).round() as i64);
+        table.insert((slice.field, level, slice.time.to_bits()), slice.values);
+    }
+
+    times.sort_by(f64::total_cmp);
+    times.dedup();
+    levels.sort_by(|a, b| b.total_cmp(a)); // surface first
+    levels.dedup();
+
+    let n_grid: usize = grid.lats.len() * grid.lons.len();
+
+    // [time][lat][lon], None when the field is absent from every input
+    let surface = |field: Field| -> Result<Option<Vec<f32>>, ConvertError> {
+        if !table.keys().any(|(f, _, _)| *f == field) {
+            return Ok(None);
+        }
+
+        let mut out: Vec<f32> = Vec::with_capacity(times.len() * n_grid);
+        for time in &times {
+            let Some(values) = table.get(&(field, -1, time.to_bits())) else {
+                return Err(ConvertError::MissingSlice {
+                    field: field.name(),
+                    time: format_time(*time),
+                    level: String::new(),
+                });
+            };
+            out.extend_from_slice(values);
+        }
+
+        Ok(Some(out))
+    };
+
+    // [level][time][lat][lon]
+    let on_levels = |field: Field| -> Result<Vec<f32>, ConvertError> {
+        if !table.keys().any(|(f, _, _)| *f == field) {
+            return Ok(Vec::new());
+        }
+
+        let mut out: Vec<f32> = Vec::with_capacity(levels.len() * times.len() * n_grid);
+        for level in &levels {
+            for time in &times {
+                let key = (field, (level * 100.0).round() as i64, time.to_bits());
+                let Some(values) = table.get(&key) else {
+                    return Err(ConvertError::MissingSlice {
+                        field: field.name(),
+                        time: format_time(*time),
+                        level: format!(" at {level} hPa"),
+                    });
+                };
+                out.extend_from_slice(values);
+            }
+        }
+
+        Ok(out)
+    };
+
+    let required = |field: Field| surface(field)?.ok_or(ConvertError::MissingField(field.name()));
+
+    // --- Surface ---
+    let temperature: Vec<f32> = required(Field::Temperature2m)?;
+    let pressure_msl: Vec<f32> = required(Field::PressureMsl)?;
+    let u10: Vec<f32> = required(Field::U10)?;
+    let v10: Vec<f32> = required(Field::V10)?;
+
+    // ICON has no 100 m wind, extrapolate with the neutral 1/7 power law
+    let (u100, v100) = match (surface(Field::U100)?, surface(Field::V100)?) {
+        (Some(u100), Some(v100)) => (u100, v100),
+        _ => {
+            eprintln!("warning: no 100 m wind, extrapolating from 10 m");
+            let shear: f32 = 10f32.powf(1.0 / 7.0);
+            (
+                u10.iter().map(|u| u * shear).collect(),
+                v10.iter().map(|v| v * shear).collect(),
+            )
+        }
+    };
+
+    let relative_humidity_2m: Vec<f32> = match surface(Field::RelativeHumidity2m)? {
+        Some(humidity) => humidity,
+        None => match surface(Field::DewPoint2m)? {
+            Some(dew_point) => temperature
+                .iter()
+                .zip(&dew_point)
+                .map(|(t, td)| relative_humidity(*t, *td))
+                .collect(),
+            None => {
+                eprintln!("warning: no humidity, the atmosphere will be dry");
+                Vec::new()
+            }
+        },
+    };
+
+    let cloud = |field: Field| -> Result<Vec<f32>, ConvertError> {
+        let cover = surface(field)?;
+        if cover.is_none() {
+            eprintln!("warning: no `{}`, the layer stays clear", field.name());
+        }
+        Ok(cover.unwrap_or_default())
+    };
+
+    let (cloud_low, cloud_mid, cloud_high) = (
+        cloud(Field::CloudLow)?,
+        cloud(Field::CloudMid)?,
+        cloud(Field::CloudHigh)?,
+    );
+
+    // --- Pressure levels ---
+    let (level_u, level_v) = (on_levels(Field::LevelU)?, on_levels(Field::LevelV)?);
+    let level_temperature: Vec<f32> = on_levels(Field::LevelTemperature)?;
+    let level_geopotential: Vec<f32> = on_levels(Field::LevelGeopotential)?;
+
+    let data = WeatherData {
+        times,
+        lats: grid.lats,
+        lons: grid.lons,
+        temperature_2m: temperature.iter().map(|t| t - 273.15).collect(),
+        pressure_msl,
+        relative_humidity_2m,
+        u10,
+        v10,
+        u100,
+        v100,
+        cloud_low,
+        cloud_mid,
+        cloud_high,
+
+        levels,
+        level_u,
+        level_v,
+        level_temperature,
+        level_geopotential,
+    };
+
+    data.validate()?;
+
+    Ok(data)
+}
+
+// --- Input ---
+
+fn read_input(path: PathBuf, selection: &Selection) -> Result<Vec<Slice>, ConvertError> {
+    let mut magic = [0u8; 4];
+    File::open(&path)?.read_exact(&mut magic)?;
+
+    match &magic {
+        [b'C', b'D', b'F', 1 | 2 | 5] => netcdf::read_slices(&path, selection),
+        b"GRIB" => grib::read_slices(&path, selection),
+        [0x89, b'H', b'D', b'F'] => Err(ConvertError::Hdf5(path)),
+        _ => Err(ConvertError::UnknownFormat(path)),
+    }
+}
+
+const USAGE: &str = "usage: weather_convert [--bbox LAT_MIN,LAT_MAX,LON_MIN,LON_MAX] \
+[--start YYYY-MM-DD[THH:MM]] [--end YYYY-MM-DD[THH:MM]] [--output FILE] INPUT...";
+
+fn run() -> Result<(), ConvertError> {
+    let usage = |message: &str| ConvertError::Usage(format!("{message}\n{USAGE}"));
+
+    let mut selection = Selection {
+        lat: (-90.0, 90.0),
+        lon: (-180.0, 180.0),
+        start: f64::NEG_INFINITY,
+        end: f64::INFINITY,
+    };
+    let mut output = PathBuf::from("assets/weather/data.weather");
+    let mut inputs: Vec<PathBuf> = Vec::new();
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| usage(&format!("{arg} needs a value")))
+        };
+
+        match arg.as_str() {
+            "--bbox" => {
+                let bbox: Vec<f32> = value()?
+                    .split(',')
+                    .map(|part| part.trim().parse::<f32>())
+                    .collect::<Result<_, _>>()
+                    .map_err(|_| usage("--bbox takes four numbers"))?;
+
+                let [lat_min, lat_max, lon_min, lon_max] = bbox[..] else {
+                    return Err(usage("--bbox takes four numbers"));
+                };
+                selection.lat = (lat_min.min(lat_max), lat_min.max(lat_max));
+                selection.lon = (lon_min.min(lon_max), lon_min.max(lon_max));
+            }
+            "--start" => {
+                selection.start =
+                    parse_date_time(&value()?).ok_or_else(|| usage("bad --start date"))?
+            }
+            "--end" => {
+                selection.end = parse_date_time(&value()?).ok_or_else(|| usage("bad --end date"))?
+            }
+            "-o" | "--output" => output = PathBuf::from(value()?),
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return Ok(());
+            }
+            _ if arg.starts_with('-') => return Err(usage(&format!("unknown option {arg}"))),
+            _ => inputs.push(PathBuf::from(arg)),
+        }
+    }
+
+    if inputs.is_empty() {
+        return Err(usage("no input files"));
+    }
+
+    let mut slices: Vec<Slice> = Vec::new();
+    for input in inputs {
+        eprintln!("reading {}", input.display());
+        slices.extend(read_input(input, &selection)?);
+    }
+
+    let data: WeatherData = assemble(slices)?;
+
+    eprintln!(
+        "{} x {} grid, {} times, {} levels",
+        data.lats.len(),
+        data.lons.len(),
+        data.times.len().max(1),
+        data.levels.len()
+    );
+
+    let config = ron::ser::PrettyConfig::new().compact_arrays(true);
+    let text: String = ron::ser::to_string_pretty(&data, config)?;
+
+    if let Some(parent) = output.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&output, text)?;
+
+    eprintln!("wrote {}", output.display());
+
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("error: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const JUNE_1: f64 = 1_717_200_000.0; // 2024-06-01T00:00Z
+
+    fn slice(field: Field, level: Option<f32>, time: f64, value: f32) -> Slice {
+        Slice {
+            field,
+            level,
+            time,
+            lats: vec![48.0, 49.0],
+            lons: vec![7.0, 8.0],
+            values: vec![value; 4],
+        }
+    }
+
+    fn surface(time: f64) -> Vec<Slice> {
+        vec![
+            slice(Field::Temperature2m, None, time, 288.15),
+            slice(Field::PressureMsl, None, time, 101_325.0),
+            slice(Field::U10, None, time, 3.0),
+            slice(Field::V10, None, time, -1.0),
+        ]
+    }
+
+    fn selection() -> Selection {
+        Selection {
+            lat: (48.0, 49.0),
+            lon: (7.0, 8.0),
+            start: f64::NEG_INFINITY,
+            end: f64::INFINITY,
+        }
+    }
+
+    #[test]
+    fn days_from_civil_matches_known_dates() {
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(days_from_civil(1969, 12, 31), -1);
+        assert_eq!(days_from_civil(1900, 1, 1), -25_567);
+        assert_eq!(days_from_civil(2000, 3, 1), 11_017);
+        assert_eq!(days_from_civil(2024, 2, 29), 19_782);
+    }
+
+    #[test]
+    fn parse_date_time_accepts_the_documented_forms() {
+        assert_eq!(parse_date_time("2024-06-01"), Some(JUNE_1));
+        assert_eq!(parse_date_time("2024-06-01T06:00"), Some(JUNE_1 + 21_600.0));
+        assert_eq!(parse_date_time("2024-06-01T06Z"), Some(JUNE_1 + 21_600.0));
+        assert_eq!(
+            parse_date_time(" 2024-06-01 06:30:15 "),
+            Some(JUNE_1 + 23_415.0)
+        );
+
+        assert_eq!(parse_date_time("2024-06"), None);
+        assert_eq!(parse_date_time("2024-06-01T6h"), None);
+        assert_eq!(parse_date_time("yesterday"), None);
+    }
+
+    #[test]
+    fn format_time_round_trips() {
+        let time: f64 = parse_date_time("1999-12-31T23:45").unwrap();
+        assert_eq!(format_time(time), "1999-12-31T23:45Z");
+    }
+
+    #[test]
+    fn crop_sorts_wraps_and_keeps_a_neighbour() {
+        // north to south and 0..360, as ERA5 ships it
+        let lats: Vec<f32> = vec![50.0, 49.0, 48.0, 47.0];
+        let lons: Vec<f32> = vec![357.0, 358.0, 359.0, 0.0, 1.0];
+        let values: Vec<f32> = (0..20).map(|v| v as f32).collect();
+
+        let selection = Selection {
+            lat: (48.5, 49.5),
+            lon: (-1.5, 0.5),
+            ..selection()
+        };
+        let cropped: Slice = selection
+            .crop(Slice {
+                field: Field::U10,
+                level: None,
+                time: 0.,
+                lats,
+                lons,
+                values,
+            })
+            .unwrap();
+
+        assert_eq!(cropped.lats, vec![48.0, 49.0, 50.0]);
+        assert_eq!(cropped.lons, vec![-2.0, -1.0, 0.0, 1.0]);
+        assert_eq!(cropped.values.len(), 12);
+        assert_eq!(cropped.values[..4], [11.0, 12.0, 13.0, 14.0]);
+        assert_eq!(cropped.values[8..], [1.0, 2.0, 3.0, 4.0]);
+    }
+
+    #[test]
+    fn crop_outside_the_grid_is_empty() {
+        let selection = Selection {
+            lat: (10.0, 11.0),
+            ..selection()
+        };
+
+        let result = selection.crop(slice(Field::U10, None, 0., 0.));
+        assert!(matches!(
+            result,
+            Err(ConvertError::EmptySelection { field: "u10" })
+        ));
+    }
+
+    #[test]
+    fn assemble_orders_times_and_levels() {
+        let mut slices: Vec<Slice> = surface(JUNE_1 + 3_600.0);
+        slices.extend(surface(JUNE_1));
+        for (level, value) in [(500.0, 1.0), (850.0, 2.0)] {
+            for time in [JUNE_1, JUNE_1 + 3_600.0] {
+                slices.push(slice(Field::LevelU, Some(level), time, value));
+            }
+        }
+
+        let data: WeatherData = assemble(slices).unwrap();
+
+        assert_eq!(data.times, vec![JUNE_1, JUNE_1 + 3_600.0]);
+        assert_eq!(data.levels, vec![850.0, 500.0]);
+        assert_eq!(data.temperature_2m, vec![15.0; 8]);
+        assert_eq!(data.level_u[..8], [2.0; 8]);
+        assert_eq!(data.level_u[8..], [1.0; 8]);
+        assert!(data.level_v.is_empty());
+
+        // no 100 m wind, extrapolated from 10 m
+        assert!(data.u100.iter().all(|u| *u > 3.0));
+    }
+
+    #[test]
+    fn assemble_rejects_an_inconsistent_grid() {
+        let mut slices: Vec<Slice> = surface(JUNE_1);
+        slices[2].lons = vec![7.0, 8.5];
+
+        assert!(matches!(
+            assemble(slices),
+            Err(ConvertError::InconsistentGrid {
+                field: "u10",
+                reference: "temperature_2m"
+            })
+        ));
+    }
+
+    #[test]
+    fn assemble_reports_missing_data() {
+        let mut slices: Vec<Slice> = surface(JUNE_1);
+        slices.remove(1);
+        assert!(matches!(
+            assemble(slices),
+            Err(ConvertError::MissingField("pressure_msl"))
+        ));
+
+        let mut slices: Vec<Slice> = surface(JUNE_1);
+        slices.extend(surface(JUNE_1 + 3_600.0).into_iter().take(3));
+        assert!(matches!(
+            assemble(slices),
+            Err(ConvertError::MissingSlice { field: "v10", .. })
+        ));
+    }
+}
@@ -0,0 +1,929 @@
+// NetCDF classic reader (CDF-1, CDF-2 and CDF-5), just enough for gridded weather fields
+use std::{
+    fs::File,
+    io::{BufReader, Read, Seek, SeekFrom},
+    path::Path,
+};
+
+use thiserror::Error;
+
+use crate::{ConvertError, Field, GRAVITY, Selection, Slice, parse_date_time};
+
+#[derive(Debug, Error)]
+pub enum NetCdfError {
+    #[error("IO error while reading file: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Malformed header: {0}")]
+    Header(&'static str),
+
+    #[error("Unknown data type {0}")]
+    DataType(u32),
+
+    #[error("Variable `{0}` runs past the end of the file")]
+    Truncated(String),
+
+    #[error("{len} bytes are not a whole number of type {data_type} values")]
+    BadLength { len: usize, data_type: u32 },
+
+    #[error("Variable `{0}` is not numeric")]
+    NotNumeric(String),
+
+    #[error("Coordinate `{0}` not found")]
+    MissingCoordinate(&'static str),
+
+    #[error("Variable `{0}` not found")]
+    MissingVariable(String),
+
+    #[error("Variable `{var}` has an unexpected dimension `{dim}` of length {len}")]
+    ExtraDimension {
+        var: String,
+        dim: String,
+        len: usize,
+    },
+
+    #[error("Time units `{0}` are not understood")]
+    TimeUnits(String),
+}
+
+// ERA5 and ICON short names, units are read from the `units` attribute
+const FIELDS: [(&str, Field); 26] = [
+    // ERA5
+    ("t2m", Field::Temperature2m),
+    ("d2m", Field::DewPoint2m),
+    ("msl", Field::PressureMsl),
+    ("u10", Field::U10),
+    ("v10", Field::V10),
+    ("u100", Field::U100),
+    ("v100", Field::V100),
+    ("lcc", Field::CloudLow),
+    ("mcc", Field::CloudMid),
+    ("hcc", Field::CloudHigh),
+    ("u", Field::LevelU),
+    ("v", Field::LevelV),
+    ("t", Field::LevelTemperature),
+    ("z", Field::LevelGeopotential),
+    // ICON
+    ("T_2M", Field::Temperature2m),
+    ("TD_2M", Field::DewPoint2m),
+    ("RELHUM_2M", Field::RelativeHumidity2m),
+    ("PMSL", Field::PressureMsl),
+    ("U_10M", Field::U10),
+    ("V_10M", Field::V10),
+    ("CLCL", Field::CloudLow),
+    ("CLCM", Field::CloudMid),
+    ("CLCH", Field::CloudHigh),
+    ("U", Field::LevelU),
+    ("V", Field::LevelV),
+    ("T", Field::LevelTemperature),
+];
+
+const LAT_NAMES: [&str; 2] = ["latitude", "lat"];
+const LON_NAMES: [&str; 2] = ["longitude", "lon"];
+const TIME_NAMES: [&str; 2] = ["valid_time", "time"];
+const LEVEL_NAMES: [&str; 4] = ["pressure_level", "isobaricInhPa", "level", "plev"];
+
+#[derive(Debug, Clone)]
+enum Attribute {
+    Text(String),
+    Numbers(Vec<f64>),
+}
+
+#[derive(Debug)]
+struct Dimension {
+    name: String,
+    len: u64, // 0 for the record dimension
+}
+
+#[derive(Debug)]
+struct Variable {
+    name: String,
+    dims: Vec<usize>,
+    attributes: Vec<(String, Attribute)>,
+    data_type: u32,
+    vsize: u64,
+    begin: u64,
+}
+
+impl Variable {
+    fn attribute(&self, name: &str) -> Option<&Attribute> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value)
+    }
+
+    fn number(&self, name: &str) -> Option<f64> {
+        match self.attribute(name)? {
+            Attribute::Numbers(values) => values.first().copied(),
+            Attribute::Text(_) => None,
+        }
+    }
+
+    fn units(&self) -> &str {
+        match self.attribute("units") {
+            Some(Attribute::Text(units)) => units.trim(),
+            _ => "",
+        }
+    }
+}
+
+fn type_size(data_type: u32) -> Result<u64, NetCdfError> {
+    match data_type {
+        1 | 2 | 7 => Ok(1),
+        3 | 8 => Ok(2),
+        4 | 5 | 9 => Ok(4),
+        6 | 10 | 11 => Ok(8),
+        _ => Err(NetCdfError::DataType(data_type)),
+    }
+}
+
+// --- Header ---
+
+const NC_DIMENSION: u32 = 0x0A;
+const NC_VARIABLE: u32 = 0x0B;
+const NC_ATTRIBUTE: u32 = 0x0C;
+const STREAMING: u64 = 0xFFFF_FFFF;
+
+struct HeaderReader<R> {
+    reader: R,
+    version: u8,
+    remaining: u64, // bytes left in the file, bounds every length before it is allocated
+}
+
+impl<R: Read> HeaderReader<R> {
+    fn take(&mut self, len: u64) -> Result<(), NetCdfError> {
+        self.remaining = self
+            .remaining
+            .checked_sub(len)
+            .ok_or(NetCdfError::Header("length runs past the end of the file"))?;
+        Ok(())
+    }
+
+    fn u32(&mut self) -> Result<u32, NetCdfError> {
+        self.take(4)?;
+        let mut bytes = [0u8; 4];
+        self.reader.read_exact(&mut bytes)?;
+        Ok(u32::from_be_bytes(bytes))
+    }
+
+    fn u64(&mut self) -> Result<u64, NetCdfError> {
+        self.take(8)?;
+        let mut bytes = [0u8; 8];
+        self.reader.read_exact(&mut bytes)?;
+        Ok(u64::from_be_bytes(bytes))
+    }
+
+    // counts and lengths are 64 bit in CDF-5
+    fn non_neg(&mut self) -> Result<u64, NetCdfError> {
+        match self.version {
+            5 => self.u64(),
+            _ => self.u32().map(u64::from),
+        }
+    }
+
+    // file offsets are 64 bit from CDF-2 on
+    fn offset(&mut self) -> Result<u64, NetCdfError> {
+        match self.version {
+            1 => self.u32().map(u64::from),
+            _ => self.u64(),
+        }
+    }
+
+    // every list entry takes at least 4 bytes
+    fn count(&mut self) -> Result<u64, NetCdfError> {
+        let count: u64 = self.non_neg()?;
+
+        match count <= self.remaining / 4 {
+            true => Ok(count),
+            false => Err(NetCdfError::Header("count runs past the end of the file")),
+        }
+    }
+
+    fn padded(&mut self, len: u64) -> Result<Vec<u8>, NetCdfError> {
+        let padded: u64 = len
+            .checked_next_multiple_of(4)
+            .ok_or(NetCdfError::Header("length runs past the end of the file"))?;
+        self.take(padded)?;
+
+        let mut bytes = vec![0u8; padded as usize];
+        self.reader.read_exact(&mut bytes)?;
+        bytes.truncate(len as usize);
+        Ok(bytes)
+    }
+
+    fn name(&mut self) -> Result<String, NetCdfError> {
+        let len: u64 = self.non_neg()?;
+        let bytes: Vec<u8> = self.padded(len)?;
+        String::from_utf8(bytes).map_err(|_| NetCdfError::Header("name is not UTF-8"))
+    }
+
+    // (tag, count), ABSENT is a zero tag with a zero count
+    fn list(&mut self, tag: u32) -> Result<u64, NetCdfError> {
+        let (found, count) = (self.u32()?, self.count()?);
+
+        match found {
+            0 if count == 0 => Ok(0),
+            found if found == tag => Ok(count),
+            _ => Err(NetCdfError::Header("unexpected list tag")),
+        }
+    }
+
+    fn attributes(&mut self) -> Result<Vec<(String, Attribute)>, NetCdfError> {
+        let count: u64 = self.list(NC_ATTRIBUTE)?;
+
+        (0..count)
+            .map(|_| {
+                let name: String = self.name()?;
+                let data_type: u32 = self.u32()?;
+                let len: u64 = self
+                    .non_neg()?
+                    .checked_mul(type_size(data_type)?)
+                    .ok_or(NetCdfError::Header("attribute length overflows"))?;
+                let bytes: Vec<u8> = self.padded(len)?;
+
+                let value = match data_type {
+                    2 => Attribute::Text(
+                        String::from_utf8_lossy(&bytes)
+                            .trim_end_matches('\0')
+                            .to_string(),
+                    ),
+                    _ => Attribute::Numbers(decode(&bytes, data_type)?),
+                };
+
+                Ok((name, value))
+            })
+            .collect()
+    }
+}
+
+// big endian values of any numeric type as f64
+fn decode(bytes: &[u8], data_type: u32) -> Result<Vec<f64>, NetCdfError> {
+    let size: usize = type_size(data_type)? as usize;
+
+    // a truncated variable would otherwise lose its last value without a word
+    if !bytes.len().is_multiple_of(size) {
+        return Err(NetCdfError::BadLength {
+            len: bytes.len(),
+            data_type,
+        });
+    }
+
+    bytes
+        .chunks_exact(size)
+        .map(|chunk| {
+            Ok(match data_type {
+                1 => chunk[0] as i8 as f64,
+                7 => chunk[0] as f64,
+                3 => i16::from_be_bytes(array(chunk, data_type)?) as f64,
+                8 => u16::from_be_bytes(array(chunk, data_type)?) as f64,
+                4 => i32::from_be_bytes(array(chunk, data_type)?) as f64,
+                9 => u32::from_be_bytes(array(chunk, data_type)?) as f64,
+                5 => f32::from_be_bytes(array(chunk, data_type)?) as f64,
+                6 => f64::from_be_bytes(array(chunk, data_type)?),
+                10 => i64::from_be_bytes(array(chunk, data_type)?) as f64,
+                11 => u64::from_be_bytes(array(chunk, data_type)?) as f64,
+                _ => return Err(NetCdfError::DataType(data_type)),
+            })
+        })
+        .collect()
+}
+
+fn array<const N: usize>(chunk: &[u8], data_type: u32) -> Result<[u8; N], NetCdfError> {
+    chunk.try_into().map_err(|_| NetCdfError::BadLength {
+        len: chunk.len(),
+        data_type,
+    })
+}
+
+// --- File ---
+
+struct NetCdf<R> {
+    file: R,
+    len: u64, // bytes
+    records: u64,
+    record_size: u64,
+    dims: Vec<Dimension>,
+    vars: Vec<Variable>,
+}
+
+impl NetCdf<BufReader<File>> {
+    fn open(path: &Path) -> Result<Self, NetCdfError> {
+        Self::new(BufReader::new(File::open(path)?))
+    }
+}
+
+impl<R: Read + Seek> NetCdf<R> {
+    fn new(mut reader: R) -> Result<Self, NetCdfError> {
+        let len: u64 = reader.seek(SeekFrom::End(0))?;
+        reader.rewind()?;
+
+        let mut header = HeaderReader {
+            reader,
+            version: 0,
+            remaining: len,
+        };
+
+        header.take(4)?;
+        let mut magic = [0u8; 4];
+        header.reader.read_exact(&mut magic)?;
+        header.version = magic[3];
+
+        let mut records: u64 = header.non_neg()?;
+
+        let dims: Vec<Dimension> = (0..header.list(NC_DIMENSION)?)
+            .map(|_| {
+                Ok(Dimension {
+                    name: header.name()?,
+                    len: header.non_neg()?,
+                })
+            })
+            .collect::<Result<_, NetCdfError>>()?;
+
+        header.attributes()?; // global attributes
+
+        let vars: Vec<Variable> = (0..header.list(NC_VARIABLE)?)
+            .map(|_| {
+                let name: String = header.name()?;
+                let dims: Vec<usize> = (0..header.count()?)
+                    .map(|_| header.non_neg().map(|dim| dim as usize))
+                    .collect::<Result<_, NetCdfError>>()?;
+
+                Ok(Variable {
+                    name,
+                    dims,
+                    attributes: header.attributes()?,
+                    data_type: header.u32()?,
+                    vsize: header.non_neg()?,
+                    begin: header.offset()?,
+                })
+            })
+            .collect::<Result<_, NetCdfError>>()?;
+
+        if vars
+            .iter()
+            .flat_map(|var| &var.dims)
+            .any(|dim| *dim >= dims.len())
+        {
+            return Err(NetCdfError::Header("dimension id out of range"));
+        }
+
+        let file: R = header.reader;
+
+        // --- Records ---
+        let is_record = |var: &Variable| var.dims.first().is_some_and(|dim| dims[*dim].len == 0);
+        let record_vars: Vec<&Variable> = vars.iter().filter(|var| is_record(var)).collect();
+
+        // a single record variable is not padded
+        let record_size: u64 = match record_vars[..] {
+            [var] => {
+                let slab: u64 = var.dims[1..].iter().map(|dim| dims[*dim].len).product();
+                slab * type_size(var.data_type)?
+            }
+            _ => record_vars.iter().map(|var| var.vsize).sum(),
+        };
+
+        if records == STREAMING && record_size > 0 {
+            let first: u64 = record_vars.iter().map(|var| var.begin).min().unwrap_or(0);
+            records = len.saturating_sub(first) / record_size;
+        }
+
+        Ok(Self {
+            file,
+            len,
+            records,
+            record_size,
+            dims,
+            vars,
+        })
+    }
+
+    fn variable(&self, name: &str) -> Option<&Variable> {
+        self.vars.iter().find(|var| var.name == name)
+    }
+
+    fn dim_len(&self, dim: usize) -> usize {
+        match self.dims[dim].len {
+            0 => self.records as usize,
+            len => len as usize,
+        }
+    }
+
+    // whole variable in C order, packing and fill values applied, missing values are NaN
+    fn read(&mut self, name: &str) -> Result<Vec<f64>, NetCdfError> {
+        let Some(var) = self.variable(name) else {
+            return Err(NetCdfError::MissingVariable(name.to_string()));
+        };
+        if var.data_type == 2 {
+            return Err(NetCdfError::NotNumeric(var.name.clone()));
+        }
+
+        let size: u64 = type_size(var.data_type)?;
+        let (begin, data_type) = (var.begin, var.data_type);
+
+        let scale: f64 = var.number("scale_factor").unwrap_or(1.0);
+        let offset: f64 = var.number("add_offset").unwrap_or(0.0);
+        let fill: [Option<f64>; 2] = [var.number("_FillValue"), var.number("missing_value")];
+
+        let record: bool = var.dims.first().is_some_and(|dim| self.dims[*dim].len == 0);
+        let (chunks, slab): (u64, u64) = match record {
+            true => (
+                self.records,
+                var.dims[1..]
+                    .iter()
+                    .map(|dim| self.dims[*dim].len)
+                    .product(),
+            ),
+            false => (1, var.dims.iter().map(|dim| self.dims[*dim].len).product()),
+        };
+
+        // the header sizes are only trusted once they fit the file
+        let fits = || -> Option<bool> {
+            let bytes: u64 = slab.checked_mul(size)?;
+            let last: u64 = chunks.saturating_sub(1).checked_mul(self.record_size)?;
+            let end: u64 = begin.checked_add(last)?.checked_add(bytes)?;
+
+            Some(chunks == 0 || (end <= self.len && bytes.checked_mul(chunks)? <= self.len))
+        };
+        if fits() != Some(true) {
+            return Err(NetCdfError::Truncated(name.to_string()));
+        }
+
+        let mut raw: Vec<f64> = Vec::with_capacity((chunks * slab) as usize);
+        let mut bytes: Vec<u8> = vec![0u8; (slab * size) as usize];
+
+        for chunk in 0..chunks {
+            self.file
+                .seek(SeekFrom::Start(begin + chunk * self.record_size))?;
+            self.file.read_exact(&mut bytes)?;
+            raw.extend(decode(&bytes, data_type)?);
+        }
+
+        Ok(raw
+            .into_iter()
+            .map(|value| match fill.contains(&Some(value)) {
+                true => f64::NAN,
+                false => value * scale + offset,
+            })
+            .collect())
+    }
+
+    // 1D coordinate variable, looked up by any of its common names
+    fn coordinate(&self, names: &[&'static str]) -> Option<(&'static str, usize)> {
+        names.iter().find_map(|name| {
+            let var = self.variable(name)?;
+            match var.dims[..] {
+                [dim] => Some((*name, dim)),
+                _ => None,
+            }
+        })
+    }
+}
+
+// seconds since the unix epoch from CF "<unit> since <date>" units
+fn time_scale(units: &str) -> Result<(f64, f64), NetCdfError> {
+    let error = || NetCdfError::TimeUnits(units.to_string());
+
+    let (unit, epoch) = units.split_once(" since ").ok_or_else(error)?;
+    let scale: f64 = match unit.trim() {
+        "seconds" | "second" | "s" => 1.0,
+        "minutes" | "minute" => 60.0,
+        "hours" | "hour" | "h" => 3_600.0,
+        "days" | "day" | "d" => 86_400.0,
+        _ => return Err(error()),
+    };
+
+    Ok((scale, parse_date_time(epoch).ok_or_else(error)?))
+}
+
+// --- Slices ---
+
+pub fn read_slices(path: &Path, selection: &Selection) -> Result<Vec<Slice>, ConvertError> {
+    file_slices(NetCdf::open(path)?, selection)
+}
+
+fn file_slices<R: Read + Seek>(
+    mut file: NetCdf<R>,
+    selection: &Selection,
+) -> Result<Vec<Slice>, ConvertError> {
+    let (lat_name, lat_dim) = file
+        .coordinate(&LAT_NAMES)
+        .ok_or(NetCdfError::MissingCoordinate("latitude"))?;
+    let (lon_name, lon_dim) = file
+        .coordinate(&LON_NAMES)
+        .ok_or(NetCdfError::MissingCoordinate("longitude"))?;
+    let time = file.coordinate(&TIME_NAMES);
+    let level = file.coordinate(&LEVEL_NAMES);
+
+    let lats: Vec<f32> = file.read(lat_name)?.iter().map(|v| *v as f32).collect();
+    let lons: Vec<f32> = file.read(lon_name)?.iter().map(|v| *v as f32).collect();
+
+    let times: Vec<f64> = match time {
+        Some((name, _)) => {
+            let (scale, epoch) = time_scale(file.variable(name).map_or("", Variable::units))?;
+            file.read(name)?
+                .iter()
+                .map(|t| (epoch + t * scale).round())
+                .collect()
+        }
+        None => vec![selection.start.max(0.)],
+    };
+
+    let levels: Vec<f32> = match level {
+        Some((name, _)) => {
+            let pascal: bool = file.variable(name).is_some_and(|var| var.units() == "Pa");
+            file.read(name)?
+                .iter()
+                .map(|p| match pascal {
+                    true => (*p / 100.0) as f32,
+                    false => *p as f32,
+                })
+                .collect()
+        }
+        None => Vec::new(),
+    };
+
+    let mut slices: Vec<Slice> = Vec::new();
+
+    for (name, field) in FIELDS {
+        let Some(var) = file.variable(name) else {
+            continue;
+        };
+
+        // surface names on a level grid are a different variable, skip them
+        if field.on_levels() != level.is_some_and(|(_, dim)| var.dims.contains(&dim)) {
+            continue;
+        }
+
+        // --- Units into the Field unit ---
+        let units: String = var.units().to_string();
+        let convert = |value: f64| -> f32 {
+            let value = match (field, units.as_str()) {
+                (Field::Temperature2m | Field::DewPoint2m | Field::LevelTemperature, _)
+                    if units.starts_with("deg") || units == "C" =>
+                {
+                    value + 273.15
+                }
+                (Field::PressureMsl, "hPa") => value * 100.0,
+                (Field::RelativeHumidity2m, "1" | "fraction" | "(0 - 1)") => value * 100.0,
+                (Field::CloudLow | Field::CloudMid | Field::CloudHigh, "%") => value / 100.0,
+                (Field::LevelGeopotential, "m" | "gpm") => value * GRAVITY as f64,
+                _ => value,
+            };
+            value as f32
+        };
+
+        // --- Strides ---
+        let dims: Vec<usize> = var.dims.clone();
+        let shape: Vec<usize> = dims.iter().map(|dim| file.dim_len(*dim)).collect();
+        let mut strides: Vec<usize> = vec![1; dims.len()];
+        for i in (0..dims.len().saturating_sub(1)).rev() {
+            strides[i] = strides[i + 1] * shape[i + 1];
+        }
+
+        let stride = |dim: Option<usize>| -> usize {
+            dim.and_then(|dim| dims.iter().position(|d| *d == dim))
+                .map_or(0, |i| strides[i])
+        };
+        let (lat_stride, lon_stride) = (stride(Some(lat_dim)), stride(Some(lon_dim)));
+        let time_stride: usize = stride(time.map(|(_, dim)| dim));
+        let level_stride: usize = stride(level.map(|(_, dim)| dim));
+
+        let known = [
+            Some(lat_dim),
+            Some(lon_dim),
+            time.map(|t| t.1),
+            level.map(|l| l.1),
+        ];
+        for (dim, len) in dims.iter().zip(&shape) {
+            if !known.contains(&Some(*dim)) && *len > 1 {
+                return Err(ConvertError::from(NetCdfError::ExtraDimension {
+                    var: name.to_string(),
+                    dim: file.dims[*dim].name.clone(),
+                    len: *len,
+                }));
+            }
+        }
+
+        let values: Vec<f64> = file.read(name)?;
+
+        let slots: Vec<Option<f32>> = match field.on_levels() {
+            true => levels.iter().copied().map(Some).collect(),
+            false => vec![None],
+        };
+
+        for (t, time) in times.iter().enumerate() {
+            if !selection.contains_time(*time) {
+                continue;
+            }
+
+            for (k, level) in slots.iter().enumerate() {
+                let base: usize = t * time_stride + k * level_stride;
+                let grid: Vec<f32> = (0..lats.len())
+                    .flat_map(|i| (0..lons.len()).map(move |j| (i, j)))
+                    .map(|(i, j)| convert(values[base + i * lat_stride + j * lon_stride]))
+                    .collect();
+
+                slices.push(selection.crop(Slice {
+                    field,
+                    level: *level,
+                    time: *time,
+                    lats: lats.clone(),
+                    lons: lons.clone(),
+                    values: grid,
+                })?);
+            }
+        }
+    }
+
+    Ok(slices)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    const SHORT: u32 = 3;
+    const FLOAT: u32 = 5;
+    const DOUBLE: u32 = 6;
+
+    struct Var {
+        name: &'static str,
+        dims: Vec<u32>,
+        attributes: Vec<(&'static str, Attribute)>,
+        data_type: u32,
+        data: Vec<u8>, // big endian, all records
+    }
+
+    fn floats(values: &[f32]) -> Vec<u8> {
+        values.iter().flat_map(|v| v.to_be_bytes()).collect()
+    }
+
+    fn shorts(values: &[i16]) -> Vec<u8> {
+        values.iter().flat_map(|v| v.to_be_bytes()).collect()
+    }
+
+    fn text(value: &str) -> Attribute {
+        Attribute::Text(value.to_string())
+    }
+
+    fn u32(out: &mut Vec<u8>, value: usize) {
+        out.extend((value as u32).to_be_bytes());
+    }
+
+    fn pad(out: &mut Vec<u8>) {
+        out.resize(out.len().next_multiple_of(4), 0);
+    }
+
+    fn name(out: &mut Vec<u8>, name: &str) {
+        u32(out, name.len());
+        out.extend(name.as_bytes());
+        pad(out);
+    }
+
+    fn attributes(out: &mut Vec<u8>, attributes: &[(&str, Attribute)]) {
+        match attributes.len() {
+            0 => u32(out, 0),
+            _ => u32(out, NC_ATTRIBUTE as usize),
+        }
+        u32(out, attributes.len());
+
+        for (key, value) in attributes {
+            name(out, key);
+            match value {
+                Attribute::Text(text) => {
+                    u32(out, 2);
+                    u32(out, text.len());
+                    out.extend(text.as_bytes());
+                    pad(out);
+                }
+                Attribute::Numbers(numbers) => {
+                    u32(out, DOUBLE as usize);
+                    u32(out, numbers.len());
+                    out.extend(numbers.iter().flat_map(|v| v.to_be_bytes()));
+                }
+            }
+        }
+    }
+
+    // CDF-1 with the non-record variables first, then the records
+    fn cdf(dims: &[(&str, usize)], records: usize, vars: &[Var]) -> Vec<u8> {
+        let is_record = |var: &Var| {
+            var.dims
+                .first()
+                .and_then(|dim| dims.get(*dim as usize))
+                .is_some_and(|(_, len)| *len == 0)
+        };
+        let vsize = |var: &Var| match is_record(var) {
+            true => (var.data.len() / records).next_multiple_of(4),
+            false => var.data.len().next_multiple_of(4),
+        };
+
+        let header = |begins: &[usize]| {
+            let mut out: Vec<u8> = b"CDF\x01".to_vec();
+            u32(&mut out, records);
+
+            u32(&mut out, NC_DIMENSION as usize);
+            u32(&mut out, dims.len());
+            for (dim, len) in dims {
+                name(&mut out, dim);
+                u32(&mut out, *len);
+            }
+
+            attributes(&mut out, &[]);
+
+            u32(&mut out, NC_VARIABLE as usize);
+            u32(&mut out, vars.len());
+            for (var, begin) in vars.iter().zip(begins) {
+                name(&mut out, var.name);
+                u32(&mut out, var.dims.len());
+                for dim in &var.dims {
+                    u32(&mut out, *dim as usize);
+                }
+                attributes(&mut out, &var.attributes);
+                u32(&mut out, var.data_type as usize);
+                u32(&mut out, vsize(var));
+                u32(&mut out, *begin);
+            }
+
+            out
+        };
+
+        // offsets are fixed width, the header length does not depend on them
+        let mut offset: usize = header(&vec![0; vars.len()]).len();
+        let mut begins: Vec<usize> = vec![0; vars.len()];
+        for record in [false, true] {
+            for (i, var) in vars.iter().enumerate() {
+                if is_record(var) == record {
+                    begins[i] = offset;
+                    offset += vsize(var);
+                }
+            }
+        }
+
+        let mut out: Vec<u8> = header(&begins);
+        for var in vars.iter().filter(|var| !is_record(var)) {
+            out.extend(&var.data);
+            pad(&mut out);
+        }
+        for record in 0..records {
+            for var in vars.iter().filter(|var| is_record(var)) {
+                let slab: usize = var.data.len() / records;
+                out.extend(&var.data[record * slab..(record + 1) * slab]);
+                pad(&mut out);
+            }
+        }
+
+        out
+    }
+
+    // 2 x 2 ERA5 style grid, one record of packed 2 m temperature with a missing value
+    fn era5(extra_dim: bool) -> Vec<u8> {
+        let mut dims = vec![("latitude", 2), ("longitude", 2), ("valid_time", 0)];
+        let mut t2m_dims = vec![2, 0, 1];
+        let mut t2m: Vec<i16> = vec![20, 22, 24, -32_767];
+
+        if extra_dim {
+            dims.push(("number", 2));
+            t2m_dims.insert(1, 3);
+            t2m.extend([0; 4]);
+        }
+
+        let coordinate = |name, data: &[f32], dims| Var {
+            name,
+            dims,
+            attributes: Vec::new(),
+            data_type: FLOAT,
+            data: floats(data),
+        };
+
+        cdf(
+            &dims,
+            1,
+            &[
+                coordinate("latitude", &[49.0, 48.0], vec![0]),
+                coordinate("longitude", &[7.0, 8.0], vec![1]),
+                Var {
+                    attributes: vec![("units", text("hours since 2024-06-01"))],
+                    ..coordinate("valid_time", &[6.0], vec![2])
+                },
+                Var {
+                    name: "t2m",
+                    dims: t2m_dims,
+                    attributes: vec![
+                        ("units", text("K")),
+                        ("scale_factor", Attribute::Numbers(vec![0.5])),
+                        ("add_offset", Attribute::Numbers(vec![270.0])),
+                        ("_FillValue", Attribute::Numbers(vec![-32_767.0])),
+                    ],
+                    data_type: SHORT,
+                    data: shorts(&t2m),
+                },
+            ],
+        )
+    }
+
+    fn selection() -> Selection {
+        Selection {
+            lat: (-90.0, 90.0),
+            lon: (-180.0, 180.0),
+            start: f64::NEG_INFINITY,
+            end: f64::INFINITY,
+        }
+    }
+
+    fn slices(bytes: Vec<u8>) -> Result<Vec<Slice>, ConvertError> {
+        file_slices(NetCdf::new(Cursor::new(bytes))?, &selection())
+    }
+
+    #[test]
+    fn reads_a_packed_field() {
+        let slices: Vec<Slice> = slices(era5(false)).unwrap();
+
+        let [slice] = &slices[..] else {
+            panic!("expected one slice, found {}", slices.len());
+        };
+        assert_eq!(slice.field, Field::Temperature2m);
+        assert_eq!(slice.time, parse_date_time("2024-06-01T06:00").unwrap());
+        assert_eq!(slice.lats, vec![48.0, 49.0]);
+        assert_eq!(slice.lons, vec![7.0, 8.0]);
+
+        // south row first once cropped, the fill value is NaN
+        assert_eq!(slice.values[0], 282.0);
+        assert!(slice.values[1].is_nan());
+        assert_eq!(slice.values[2..], [280.0, 281.0]);
+    }
+
+    #[test]
+    fn truncated_data_is_an_error() {
+        let mut bytes: Vec<u8> = era5(false);
+        bytes.truncate(bytes.len() - 4);
+
+        assert!(matches!(
+            slices(bytes),
+            Err(ConvertError::NetCdf(NetCdfError::Truncated(name))) if name == "t2m"
+        ));
+    }
+
+    #[test]
+    fn truncated_header_is_an_error() {
+        let bytes: Vec<u8> = era5(false);
+
+        for len in [0, 3, 9, 40, 100] {
+            assert!(NetCdf::new(Cursor::new(bytes[..len].to_vec())).is_err());
+        }
+    }
+
+    #[test]
+    fn lengths_past_the_end_are_not_allocated() {
+        let mut header: Vec<u8> = b"CDF\x01".to_vec();
+        u32(&mut header, 0);
+        u32(&mut header, NC_DIMENSION as usize);
+        u32(&mut header, u32::MAX as usize);
+        assert!(matches!(
+            NetCdf::new(Cursor::new(header)),
+            Err(NetCdfError::Header(_))
+        ));
+
+        let mut header: Vec<u8> = b"CDF\x01".to_vec();
+        u32(&mut header, 0);
+        attributes(&mut header, &[]); // no dimensions
+        u32(&mut header, NC_ATTRIBUTE as usize);
+        u32(&mut header, 1);
+        name(&mut header, "history");
+        u32(&mut header, 2);
+        u32(&mut header, 0x7FFF_FFFF);
+        assert!(matches!(
+            NetCdf::new(Cursor::new(header)),
+            Err(NetCdfError::Header(_))
+        ));
+    }
+
+    #[test]
+    fn inconsistent_grids_are_errors() {
+        // t2m over an ensemble dimension the weather grid has no room for
+        assert!(matches!(
+            slices(era5(true)),
+            Err(ConvertError::NetCdf(NetCdfError::ExtraDimension { var, dim, len: 2 }))
+                if var == "t2m" && dim == "number"
+        ));
+
+        let bytes: Vec<u8> = cdf(
+            &[("latitude", 2)],
+            0,
+            &[Var {
+                name: "latitude",
+                dims: vec![1],
+                attributes: Vec::new(),
+                data_type: FLOAT,
+                data: floats(&[48.0, 49.0]),
+            }],
+        );
+        assert!(matches!(
+            NetCdf::new(Cursor::new(bytes)),
+            Err(NetCdfError::Header("dimension id out of range"))
+        ));
+    }
+}
@@ -0,0 +1,99 @@
+use bevy::prelude::*;
+
+use crate::{
+    cf104::{Joystick, RotRange2D},
+    player::{Player, camera::CameraShake},
+    projectile::{
+        AngularVelocity, Crashed, Grounded, air_data::AirData, control_surfaces::body_rates,
+    },
+};
+
+// Automatic Pitch Control, shakes the stick ahead of the pitch-up and then pushes it forward
+#[derive(Component, Debug)]
+pub struct Apc {
+    pub enabled: bool, // cockpit cutout switch
+
+    pub shaker_alpha: f32,    // rad
+    pub kicker_alpha: f32,    // rad
+    pub pitch_rate_gain: f32, // s, pitch rate lead on alpha
+    pub min_airspeed: f32,    // m/s, vanes are meaningless below this
+
+    pub kick_rate: f32,       // stick travel per s
+    pub shake_amplitude: f32, // m
+
+    // state
+    pub shaking: bool,
+    pub kicking: bool,
+}
+
+impl Apc {
+    pub fn cf_104() -> Self {
+        Self {
+            enabled: true,
+
+            shaker_alpha: 13f32.to_radians(),
+            kicker_alpha: 15.5f32.to_radians(),
+            pitch_rate_gain: 0.2,
+            min_airspeed: 50.0,
+
+            kick_rate: 3.0,
+            shake_amplitude: 0.004,
+
+            shaking: false,
+            kicking: false,
+        }
+    }
+
+    // alpha with pitch rate lead, a fast pull reaches the boundary before alpha does
+    pub fn effective_alpha(&self, alpha: f32, pitch_rate: f32) -> f32 {
+        alpha + self.pitch_rate_gain * pitch_rate.max(0.)
+    }
+}
+
+pub fn update_apc(
+    time: Res<Time>,
+    mut plane: Single<
+        (
+            &mut Apc,
+            &AirData,
+            &AngularVelocity,
+            Has<Grounded>,
+            Has<Crashed>,
+        ),
+        With<Player>,
+    >,
+    mut joystick: Single<(&mut Joystick, &RotRange2D, &mut Transform)>,
+    mut camera_shake: Single<&mut CameraShake>,
+) {
+    let dt: f32 = time.delta_secs();
+
+    let (ref mut apc, air_data, angular_velocity, grounded, crashed) = *plane;
+
+    let active: bool =
+        apc.enabled && !grounded && !crashed && air_data.true_airspeed > apc.min_airspeed;
+
+    let (_, q, _) = body_rates(angular_velocity.0);
+    let alpha: f32 = apc.effective_alpha(air_data.alpha, q);
+
+    apc.shaking = active && alpha > apc.shaker_alpha;
+    apc.kicking = active && alpha > apc.kicker_alpha;
+
+    // --- Shaker ---
+    if apc.shaking {
+        camera_shake.amplitude += Vec3::splat(apc.shake_amplitude);
+    }
+
+    // --- Kicker ---
+    // forward stick is -y, overrides whatever the pilot is holding
+    if apc.kicking {
+        let (ref mut joystick, range, ref mut transform) = *joystick;
+
+        // 1/s, about the 0.15 per frame of the pilot's stick at 60 fps
+        const STICK_FOLLOW_RATE: f32 = 10.0;
+
+        joystick.0.y = (joystick.0.y - apc.kick_rate * dt).max(-1.0);
+
+        let blend: f32 = 1.0 - (-STICK_FOLLOW_RATE * dt).exp();
+        transform.rotation = transform.rotation.slerp(range.to_quat(joystick.0), blend);
+    }
+}
@@ -2,7 +2,7 @@ use std::f32::consts::TAU;
 
 use bevy::{camera::visibility::NoFrustumCulling, prelude::*};
 
-use crate::{cf104::CF104_CONSOLE_ASSET_PATH, world::GlobalPosition};
+use crate::{cf104::CF104_CONSOLE_ASSET_PATH, projectile::interpolation::Interpolated};
 
 #[derive(Component)]
 pub struct Altimeter(Entity, Entity, Entity, Entity, Entity);
@@ -11,7 +11,7 @@ pub struct Altimeter(Entity, Entity, Entity, Entity, Entity);
 pub struct AltimeterTmp;
 
 pub fn update_altimeter(
-    parent_query: Query<&GlobalPosition>,
+    parent_query: Query<&Interpolated>,
     altimeter_query: Query<(&Altimeter)>,
     mut transform_query: Query<&mut Transform, (With<AltimeterTmp>, Without<Altimeter>)>,
 ) {
@@ -29,8 +29,11 @@ pub fn update_altimeter(
         let Ok(parent) = parent_query.get(target) else {
             panic!("Invalid state")
         };
+        let Some(parent) = parent.render() else {
+            continue;
+        };
 
-        let altitude: f32 = (parent.y as f32 + 156.) * 3.28084;
+        let altitude: f32 = (parent.position.y as f32 + 156.) * 3.28084;
 
         if let Ok(mut transform) = transform_query.get_mut(wheel_10k) {
             let angle = -(altitude / 10_000_00.0) * TAU + wheel_offset;
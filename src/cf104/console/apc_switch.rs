@@ -0,0 +1,92 @@
+use bevy::{camera::visibility::NoFrustumCulling, prelude::*};
+
+use crate::{
+    cf104::{
+        apc::Apc,
+        console::{RotRange, Toggle},
+    },
+    player::{
+        Player,
+        camera::{MaskMaterials, mask_mesh},
+    },
+};
+
+#[derive(Component, Debug)]
+pub struct ApcSwitch;
+
+pub fn spawn_apc_switch(
+    transform: Transform,
+    commands: &mut Commands,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials: &mut ResMut<Assets<StandardMaterial>>,
+    mask_materials: &Res<MaskMaterials>,
+    console_material: &Handle<StandardMaterial>,
+    parent_id: Entity,
+) {
+    // panel plate
+    let mesh: Handle<Mesh> = meshes.add(Cuboid::new(0.04, 0.01, 0.04));
+
+    let panel_id = commands
+        .spawn((
+            Mesh3d(mesh),
+            MeshMaterial3d(console_material.clone()),
+            NoFrustumCulling,
+            transform,
+            ChildOf(parent_id),
+        ))
+        .id();
+
+    // lever pivots at its base, guarded yellow so it is not flipped by mistake
+    let lever: Handle<Mesh> =
+        meshes.add(Mesh::from(Cylinder::new(0.004, 0.03)).translated_by(Vec3::new(0., -0.015, 0.)));
+
+    let lever_material = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.9, 0.75, 0.1),
+        ..default()
+    });
+
+    let range = RotRange {
+        min: Quat::from_rotation_x(-0.5), // cutout
+        max: Quat::from_rotation_x(0.5),  // normal
+    };
+
+    let mut transform = Transform::default();
+    transform.translation = Vec3 {
+        x: 0.,
+        y: -0.005,
+        z: 0.,
+    };
+    transform.rotation = range.max;
+
+    let switch_id = commands
+        .spawn((
+            Mesh3d(lever.clone()),
+            MeshMaterial3d(lever_material),
+            ApcSwitch,
+            Toggle(true),
+            range,
+            Name::new("APC Switch"),
+            NoFrustumCulling,
+            transform,
+            ChildOf(panel_id),
+        ))
+        .id();
+
+    mask_mesh::<false>(mask_materials, lever, switch_id, commands);
+}
+
+pub fn update_apc_switch(
+    mut switches: Query<(&Toggle, &RotRange, &mut Transform), With<ApcSwitch>>,
+    mut apc: Single<&mut Apc, With<Player>>,
+) {
+    for (toggle, range, mut transform) in &mut switches {
+        let target_rotation: Quat = match toggle.0 {
+            true => range.max,
+            false => range.min,
+        };
+
+        transform.rotation = transform.rotation.slerp(target_rotation, 0.3);
+
+        apc.enabled = toggle.0;
+    }
+}
@@ -0,0 +1,111 @@
+use bevy::{camera::visibility::NoFrustumCulling, prelude::*};
+
+use crate::{
+    cf104::console::{RotRange, Toggle},
+    player::{
+        Player,
+        camera::{MaskMaterials, mask_mesh},
+    },
+    projectile::mass::FuelSystem,
+};
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FuelSwitch {
+    Transfer,
+    Pressurization,
+    Jettison,
+}
+
+pub fn spawn_fuel_panel(
+    transform: Transform,
+    commands: &mut Commands,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials: &mut ResMut<Assets<StandardMaterial>>,
+    mask_materials: &Res<MaskMaterials>,
+    console_material: &Handle<StandardMaterial>,
+    parent_id: Entity,
+) {
+    // panel plate
+    let mesh: Handle<Mesh> = meshes.add(Cuboid::new(0.12, 0.01, 0.05));
+
+    let panel_id = commands
+        .spawn((
+            Mesh3d(mesh.clone()),
+            MeshMaterial3d(console_material.clone()),
+            NoFrustumCulling,
+            transform,
+            ChildOf(parent_id),
+        ))
+        .id();
+
+    // levers pivot at their base
+    let lever: Handle<Mesh> =
+        meshes.add(Mesh::from(Cylinder::new(0.004, 0.03)).translated_by(Vec3::new(0., -0.015, 0.)));
+
+    let lever_material = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.75, 0.75, 0.75),
+        metallic: 0.8,
+        perceptual_roughness: 0.3,
+        ..default()
+    });
+    let jettison_material = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.8, 0.1, 0.1),
+        ..default()
+    });
+
+    let switches = [
+        (FuelSwitch::Transfer, -0.04, lever_material.clone()),
+        (FuelSwitch::Pressurization, 0., lever_material),
+        (FuelSwitch::Jettison, 0.04, jettison_material),
+    ];
+
+    for (switch, x, material_handle) in switches {
+        let range = RotRange {
+            min: Quat::from_rotation_x(-0.5),
+            max: Quat::from_rotation_x(0.5),
+        };
+
+        let mut transform = Transform::default();
+        transform.translation = Vec3 {
+            x,
+            y: -0.005,
+            z: 0.,
+        };
+        transform.rotation = range.min;
+
+        let switch_id = commands
+            .spawn((
+                Mesh3d(lever.clone()),
+                MeshMaterial3d(material_handle),
+                switch,
+                Toggle::default(),
+                range,
+                NoFrustumCulling,
+                transform,
+                ChildOf(panel_id),
+            ))
+            .id();
+
+        mask_mesh::<false>(mask_materials, lever.clone(), switch_id, commands);
+    }
+}
+
+pub fn update_fuel_panel(
+    mut switches: Query<(&FuelSwitch, &Toggle, &RotRange, &mut Transform)>,
+    mut fuel_system: Single<&mut FuelSystem, With<Player>>,
+) {
+    for (switch, toggle, range, mut transform) in &mut switches {
+        let target_rotation: Quat = match toggle.0 {
+            true => range.max,
+            false => range.min,
+        };
+
+        transform.rotation = transform.rotation.slerp(target_rotation, 0.3);
+
+        match switch {
+            FuelSwitch::Transfer => fuel_system.transfer = toggle.0,
+            FuelSwitch::Pressurization => fuel_system.pressurized = toggle.0,
+            FuelSwitch::Jettison => fuel_system.jettison = toggle.0,
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use std::{f32::consts::FRAC_PI_2, time::Duration};
+
+use bevy::{audio::Pitch, camera::visibility::NoFrustumCulling, prelude::*};
+
+use crate::{
+    cf104::console::{RotRange, throttle::Throttle},
+    player::{
+        Player,
+        camera::{MaskMaterials, mask_mesh},
+    },
+    projectile::landing_gear::LandingGear,
+    world::GlobalPosition,
+};
+
+#[derive(Component, Debug)]
+pub struct GearLever(pub bool); // true -> gear down
+
+impl Default for GearLever {
+    fn default() -> Self {
+        Self(true)
+    }
+}
+
+#[derive(Component, Debug)]
+pub struct GearHorn {
+    pub max_height: f32,   // m above the field
+    pub max_throttle: f32, // throttle %
+}
+
+impl GearHorn {
+    pub fn cf_104() -> Self {
+        Self {
+            max_height: 3_000.0,
+            max_throttle: 30.0,
+        }
+    }
+}
+
+pub fn spawn_gear_lever(
+    transform: Transform,
+    commands: &mut Commands,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials: &mut ResMut<Assets<StandardMaterial>>,
+    mask_materials: &Res<MaskMaterials>,
+    parent_id: Entity,
+) {
+    // lever pivots at its base, the wheel shaped knob sits at the tip
+    let lever: Handle<Mesh> =
+        meshes.add(Mesh::from(Cylinder::new(0.005, 0.08)).translated_by(Vec3::new(0., -0.04, 0.)));
+    let knob: Handle<Mesh> = meshes.add(Cylinder::new(0.015, 0.01));
+
+    let lever_material = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.75, 0.75, 0.75),
+        metallic: 0.8,
+        perceptual_roughness: 0.3,
+        ..default()
+    });
+    let knob_material = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.9, 0.9, 0.9),
+        ..default()
+    });
+
+    let range = RotRange {
+        min: Quat::from_rotation_x(-0.6), // up
+        max: Quat::from_rotation_x(0.6),  // down
+    };
+
+    let mut transform = transform;
+    transform.rotation = range.max;
+
+    let lever_id = commands
+        .spawn((
+            Mesh3d(lever.clone()),
+            MeshMaterial3d(lever_material),
+            GearLever::default(),
+            range,
+            Name::new("Gear Lever"),
+            NoFrustumCulling,
+            transform,
+            ChildOf(parent_id),
+        ))
+        .id();
+
+    mask_mesh::<false>(mask_materials, lever, lever_id, commands);
+
+    let mut knob_transform = Transform::default();
+    knob_transform.translation = Vec3 {
+        x: 0.,
+        y: -0.08,
+        z: 0.,
+    };
+    knob_transform.rotation = Quat::from_rotation_z(FRAC_PI_2);
+
+    commands.spawn((
+        Mesh3d(knob),
+        MeshMaterial3d(knob_material),
+        NoFrustumCulling,
+        knob_transform,
+        ChildOf(lever_id),
+    ));
+
+    commands.spawn((GearHorn::cf_104(), Transform::default(), ChildOf(lever_id)));
+}
+
+pub fn update_gear_lever(
+    mut levers: Query<(&GearLever, &RotRange, &mut Transform)>,
+    mut landing_gear: Single<&mut LandingGear, With<Player>>,
+) {
+    for (lever, range, mut transform) in &mut levers {
+        let target_rotation: Quat = match lever.0 {
+            true => range.max,
+            false => range.min,
+        };
+
+        transform.rotation = transform.rotation.slerp(target_rotation, 0.3);
+
+        landing_gear.lever_down = lever.0;
+    }
+}
+
+pub fn update_gear_horn(
+    mut commands: Commands,
+    mut pitches: ResMut<Assets<Pitch>>,
+    throttle: Single<&Throttle>,
+    plane: Single<(&LandingGear, &GlobalPosition), With<Player>>,
+    horns: Query<(Entity, &GearHorn, Has<AudioPlayer<Pitch>>)>,
+) {
+    let (landing_gear, position) = *plane;
+
+    for (entity, horn, sounding) in &horns {
+        let warning: bool = !landing_gear.down_and_locked()
+            && throttle.0 < horn.max_throttle
+            && (position.y as f32) < horn.max_height;
+
+        match (warning, sounding) {
+            (true, false) => {
+                commands.entity(entity).insert((
+                    AudioPlayer(pitches.add(Pitch::new(600.0, Duration::from_millis(500)))),
+                    PlaybackSettings::LOOP,
+                ));
+            }
+            (false, true) => {
+                commands
+                    .entity(entity)
+                    .remove::<(AudioPlayer<Pitch>, AudioSink, PlaybackSettings)>();
+            }
+            _ => {}
+        }
+    }
+}
@@ -2,7 +2,11 @@ use std::f32::consts::{FRAC_PI_2, PI};
 
 use bevy::{camera::visibility::NoFrustumCulling, prelude::*};
 
-use crate::cf104::CF104_CONSOLE_ASSET_PATH;
+use crate::{
+    cf104::{CF104_CONSOLE_ASSET_PATH, Plane},
+    player::Player,
+    projectile::interpolation::Interpolated,
+};
 
 #[derive(Component)]
 pub struct CompassGyro;
@@ -10,7 +14,13 @@ pub struct CompassGyro;
 pub fn update_compass_gyro(
     compass_query: Query<(&ChildOf, &mut Transform), With<CompassGyro>>,
     parent_query: Query<&GlobalTransform, Without<CompassGyro>>,
+    plane: Single<(&GlobalTransform, &Interpolated), (With<Player>, With<Plane>)>,
 ) {
+    let (plane_global, interpolated) = *plane;
+    let Some(state) = interpolated.render() else {
+        return;
+    };
+
     let global_north_rot = Quat::from_rotation_y(PI); // adjust as needed
     // let global_north = Quat::from_rotation_y(0.0);;
     // let global_up = Vec3::Y;
@@ -20,7 +30,9 @@ pub fn update_compass_gyro(
 
     for (ChildOf(parent), mut local) in compass_query {
         if let Ok(parent_global) = parent_query.get(*parent) {
-            let parent_rot = parent_global.rotation();
+            // mounting relative to the airframe, the global transforms are a frame behind
+            let mounting = plane_global.rotation().conjugate() * parent_global.rotation();
+            let parent_rot = state.rotation * mounting;
 
             // Target rotation in world space
             let target_rot = global_north_rot * offset_rot;
@@ -1,19 +1,28 @@
-use bevy::prelude::*;
+use bevy::{prelude::*, transform::TransformSystems};
 
-use crate::cf104::console::{
-    altimeter::update_altimeter,
-    clock::update_clock,
-    gyro_compass::update_compass_gyro,
-    radio::{
-        DeferredFxChange, Radio, RadioChannelConfig, RadioChannelLoader, RadioChannels,
-        UpdateRadioFx, UpdateVolume, deferred_fx_change, load_channels, set_up_radio_audio,
-        update_fx_selector, update_radio, update_volume_knob,
+use crate::{
+    cf104::console::{
+        altimeter::update_altimeter,
+        apc_switch::update_apc_switch,
+        clock::update_clock,
+        fuel_panel::update_fuel_panel,
+        gear_lever::{update_gear_horn, update_gear_lever},
+        gyro_compass::update_compass_gyro,
+        radio::{
+            DeferredFxChange, Radio, RadioChannelConfig, RadioChannelLoader, RadioChannels,
+            UpdateRadioFx, UpdateVolume, deferred_fx_change, load_channels, set_up_radio_audio,
+            update_fx_selector, update_radio, update_volume_knob,
+        },
+        speedometer::update_speedometer,
     },
-    speedometer::update_speedometer,
+    projectile::interpolation::InterpolationSet,
 };
 
 pub mod altimeter;
+pub mod apc_switch;
 pub mod clock;
+pub mod fuel_panel;
+pub mod gear_lever;
 pub mod gyro_compass;
 pub mod radio;
 pub mod speedometer;
@@ -25,6 +34,10 @@ pub struct RotRange {
     pub max: Quat,
 }
 
+// two position switch, flipped by fuel panel / apc switch controllers
+#[derive(Component, Debug, Default)]
+pub struct Toggle(pub bool);
+
 pub struct ConsolePlugin;
 
 impl Plugin for ConsolePlugin {
@@ -40,15 +53,22 @@ impl Plugin for ConsolePlugin {
                 Update,
                 (
                     update_clock,
-                    update_compass_gyro,
-                    update_altimeter,
-                    update_speedometer,
+                    update_fuel_panel,
+                    update_gear_lever,
+                    update_gear_horn,
+                    update_apc_switch,
                     update_fx_selector,
                     update_volume_knob,
                     update_radio,
                     deferred_fx_change,
                 ),
             )
+            .add_systems(
+                PostUpdate,
+                (update_compass_gyro, update_altimeter, update_speedometer)
+                    .after(InterpolationSet)
+                    .before(TransformSystems::Propagate),
+            )
             .add_systems(Startup, load_channels)
             .add_systems(PostStartup, set_up_radio_audio);
     }
@@ -2,8 +2,9 @@ use std::f32::consts::PI;
 
 use bevy::{camera::visibility::NoFrustumCulling, prelude::*};
 
-use crate::cf104::CF104_CONSOLE_ASSET_PATH;
-use crate::projectile::Velocity;
+use crate::cf104::{CF104_CONSOLE_ASSET_PATH, Plane};
+use crate::player::Player;
+use crate::projectile::interpolation::Interpolated;
 
 #[derive(Component)]
 pub struct Speedometer(Entity);
@@ -13,13 +14,17 @@ pub struct SpeedometerNeedle;
 pub fn update_speedometer(
     query: Query<&Speedometer>,
     mut needle_query: Query<&mut Transform, With<SpeedometerNeedle>>,
-    velocity: Single<(&GlobalTransform, &Velocity)>,
+    plane: Single<&Interpolated, (With<Player>, With<Plane>)>,
 ) {
     let deg_to_rad = PI / 180.0;
 
+    let Some(state) = plane.render() else {
+        return;
+    };
+
     for Speedometer(needle) in query {
-        let forward = *velocity.0.right();
-        let forward_speed = velocity.1.0.project_onto(forward).length();
+        let forward = state.rotation * Vec3::X;
+        let forward_speed = state.velocity.project_onto(forward).length();
 
         let mach: f32 = forward_speed / 343.0;
 
@@ -1,28 +1,39 @@
 use std::f32::consts::{FRAC_PI_2, PI};
 
-use bevy::{audio::Volume, camera::visibility::NoFrustumCulling, prelude::*, time::Stopwatch};
+use bevy::{audio::Volume, camera::visibility::NoFrustumCulling, prelude::*};
 
 use crate::{
-    cf104::console::{
-        ConsolePlugin, RotRange,
-        altimeter::spawn_altimeter,
-        clock::spawn_clock,
-        gyro_compass::spawn_gyro_compass,
-        radio::spawn_radio,
-        speedometer::spawn_speedometer,
-        throttle::{Throttle, spawn_throttle},
+    cf104::{
+        apc::{Apc, update_apc},
+        console::{
+            ConsolePlugin, RotRange,
+            altimeter::spawn_altimeter,
+            apc_switch::spawn_apc_switch,
+            clock::spawn_clock,
+            fuel_panel::spawn_fuel_panel,
+            gear_lever::spawn_gear_lever,
+            gyro_compass::spawn_gyro_compass,
+            radio::spawn_radio,
+            speedometer::spawn_speedometer,
+            throttle::spawn_throttle,
+        },
     },
     player::{
         Player,
-        camera::{CameraShake, MaskMaterials, mask_mesh, set_up_player_camera},
+        camera::{
+            CameraShake, MaskMaterials, mask_mesh, set_up_player_camera, update_camera_shake,
+        },
+        controls::joystick_controller,
     },
     projectile::{
         GroundedBundle, PlaneBundle,
         drag::DragTarget,
+        engine::Engine,
         mass::{ExternalFuelTankBundle, InternalFuelTankBundle, MassBundle},
     },
 };
 
+pub mod apc;
 pub mod console;
 
 // CF104
@@ -82,8 +93,6 @@ pub struct EngineAudio {
     pub spool_up: Handle<AudioSource>,
     pub running_loop: Handle<AudioSource>,
     pub loop_instance: Option<Handle<AudioSource>>,
-    pub stopwatch: Stopwatch,
-    pub spool_duration: f32,
     running: bool,
 }
 
@@ -93,44 +102,37 @@ impl EngineAudio {
             spool_up: asset_server.load("cf104/spool_up.ogg"),
             running_loop: asset_server.load("cf104/running.ogg"),
             loop_instance: None,
-            stopwatch: Stopwatch::default(),
-            spool_duration: 17.0,
             running: false,
         }
     }
     pub fn start_up_engine(
         mut commands: Commands,
-        throttle: Single<&Throttle>,
+        engine: Single<&Engine, With<Player>>,
         mut query: Query<(Entity, &mut EngineAudio), Without<AudioPlayer>>,
     ) {
         for (entity, mut engine_audio) in &mut query {
-            // Start engine only when throttle applied
-            if throttle.0 > 0.05 {
+            // Start engine only once it has lit
+            if engine.lit {
                 println!("Starting engine spool-up sound...");
 
                 let audio: AudioPlayer = AudioPlayer::new(engine_audio.spool_up.clone());
 
                 engine_audio.loop_instance = Some(engine_audio.spool_up.clone());
-                engine_audio.stopwatch.reset();
+                engine_audio.running = false;
 
                 commands.entity(entity).insert(audio);
             }
         }
     }
     pub fn update_sound(
-        time: Res<Time>,
         mut commands: Commands,
-        throttle: Single<&Throttle>,
+        engine: Single<&Engine, With<Player>>,
         canopy_door: Single<&CanopyDoor>,
         mut query: Query<(Entity, &mut EngineAudio, &mut SpatialAudioSink)>,
     ) {
         let cockpit_closed: bool = canopy_door.0 <= 0.00001;
 
         for (entity, mut engine_audio, mut audio_sink) in &mut query {
-            engine_audio.stopwatch.tick(time.delta());
-
-            let throttle_factor = (throttle.0 / 100.0).clamp(0.0, 1.0);
-
             let min_volume = match cockpit_closed {
                 true => 20.0,
                 false => 40.0,
@@ -140,24 +142,30 @@ impl EngineAudio {
                 false => 60.0,
             };
 
-            let target_volume =
-                match engine_audio.stopwatch.elapsed_secs() < engine_audio.spool_duration {
-                    true => {
-                        let spool_factor = (engine_audio.stopwatch.elapsed_secs()
-                            / engine_audio.spool_duration)
-                            .clamp(0.0, 1.0);
-                        min_volume + (max_volume - min_volume) * spool_factor
-                    }
-                    false => min_volume + (max_volume - min_volume) * throttle_factor,
-                };
+            let target_volume = match engine.rpm < engine.idle_rpm {
+                true => min_volume * (engine.rpm / engine.idle_rpm).clamp(0.0, 1.0),
+                false => {
+                    let power =
+                        (0.8 * engine.core_power() + 0.2 * engine.afterburner).clamp(0.0, 1.0);
+                    min_volume + (max_volume - min_volume) * power
+                }
+            };
 
             if audio_sink.volume() != Volume::Linear(target_volume) {
                 audio_sink.set_volume(Volume::Linear(target_volume));
             }
 
-            if engine_audio.stopwatch.elapsed_secs() >= engine_audio.spool_duration
-                && !engine_audio.running
-            {
+            // shut down / flame out
+            if !engine.lit && engine.rpm < 0.5 * engine.idle_rpm {
+                commands.entity(entity).remove::<AudioPlayer>();
+                commands.entity(entity).remove::<SpatialAudioSink>();
+
+                engine_audio.loop_instance = None;
+                engine_audio.running = false;
+                continue;
+            }
+
+            if engine.rpm >= 0.98 * engine.idle_rpm && !engine_audio.running {
                 commands.entity(entity).remove::<AudioPlayer>();
                 commands.entity(entity).remove::<SpatialAudioSink>();
 
@@ -178,7 +186,13 @@ impl Plugin for CF104Plugin {
             .add_systems(Startup, initialize_player)
             .add_systems(
                 Update,
-                (EngineAudio::start_up_engine, EngineAudio::update_sound),
+                (
+                    EngineAudio::start_up_engine,
+                    EngineAudio::update_sound,
+                    update_apc
+                        .after(joystick_controller)
+                        .before(update_camera_shake),
+                ),
             );
     }
 }
@@ -186,6 +200,8 @@ impl Plugin for CF104Plugin {
 pub(crate) const CF104_BODY_ASSET_PATH: &'static str = "cf104\\meshes.gltf";
 pub(crate) const CF104_CONSOLE_ASSET_PATH: &'static str = "cf104\\cf104_console_accessories.gltf";
 pub(crate) const CF104_DOOR_ASSET_PATH: &'static str = "cf104\\cf104_door_accessories.gltf";
+pub(crate) const CF104_AERO_ASSET_PATH: &'static str = "cf104/cf104.aero";
+pub(crate) const CF104_TURBULENCE_SEED: u64 = 104;
 
 fn load_cf104<const PLAYER: bool>(
     transform: Transform,
@@ -205,7 +221,12 @@ fn load_cf104<const PLAYER: bool>(
             Player,
             Plane,
             GroundedBundle::cf_104(),
-            PlaneBundle::cf_104(transform.translation.clone()),
+            Apc::cf_104(),
+            PlaneBundle::cf_104(
+                transform.translation.clone(),
+                asset_server.load(CF104_AERO_ASSET_PATH),
+                CF104_TURBULENCE_SEED,
+            ),
             transform,
         ))
         .id();
@@ -238,7 +259,10 @@ fn load_cf104<const PLAYER: bool>(
 
         // add internal fuel
         let internal_fuel_tank = commands
-            .spawn((InternalFuelTankBundle::new(2_608.0, parent_id), ChildOf(id)))
+            .spawn((
+                InternalFuelTankBundle::new(2_608.0, Vec3::new(0.1, 0., 0.), parent_id),
+                ChildOf(id),
+            ))
             .id();
 
         let nuke = commands
@@ -602,6 +626,65 @@ fn load_cf104<const PLAYER: bool>(
                 );
             }
 
+            // fuel panel
+            {
+                let mut transform = Transform::default();
+                transform.translation = Vec3 {
+                    x: 0.30,
+                    y: -1.5688923597335815,
+                    z: 0.92,
+                };
+
+                spawn_fuel_panel(
+                    transform,
+                    commands,
+                    &mut meshes,
+                    materials,
+                    mask_materials,
+                    &console_material,
+                    console_id,
+                );
+            }
+
+            // gear lever
+            {
+                let mut transform = Transform::default();
+                transform.translation = Vec3 {
+                    x: -0.42,
+                    y: -1.5688923597335815,
+                    z: 0.92,
+                };
+
+                spawn_gear_lever(
+                    transform,
+                    commands,
+                    &mut meshes,
+                    materials,
+                    mask_materials,
+                    console_id,
+                );
+            }
+
+            // apc cutout switch
+            {
+                let mut transform = Transform::default();
+                transform.translation = Vec3 {
+                    x: -0.36,
+                    y: -1.5688923597335815,
+                    z: 0.92,
+                };
+
+                spawn_apc_switch(
+                    transform,
+                    commands,
+                    &mut meshes,
+                    materials,
+                    mask_materials,
+                    &console_material,
+                    console_id,
+                );
+            }
+
             // speedometer
             {
                 let mut transform = Transform::default();
@@ -732,7 +815,13 @@ fn load_cf104<const PLAYER: bool>(
                     Mesh3d(mesh),
                     MeshMaterial3d(material_handle),
                     NoFrustumCulling,
-                    ExternalFuelTankBundle::new(454.0 * fuel_level, parent_id, internal_tank),
+                    ExternalFuelTankBundle::new(
+                        454.0 * fuel_level,
+                        Vec3::new(0.15, 0., (i * 2 - 1) as f32 * 3.4),
+                        parent_id,
+                        internal_tank,
+                    ),
+                    DragTarget(parent_id),
                     transform,
                     ChildOf(body_id),
                 )
@@ -2,6 +2,8 @@ use crate::cf104::Plane;
 use crate::player::controls::{KeyBindings, KeyState};
 use crate::player::ui::BlackoutRedout;
 use crate::player::{Player, Selectable};
+use crate::projectile::clouds::InCloud;
+use crate::projectile::control_surfaces::body_rates;
 use crate::projectile::util::GRAVITY;
 use crate::projectile::{AngularVelocity, GForceCache};
 use bevy::camera::RenderTarget;
@@ -100,7 +102,65 @@ impl Default for CameraSensitivity {
 }
 
 #[derive(Component, Default)]
-pub struct CameraShake(Vec3);
+pub struct CameraShake {
+    pub amplitude: Vec3, // m, summed each frame from whoever wants the camera shaken
+    offset: Vec3,
+}
+
+const CAMERA_SHAKE_FREQUENCY: f32 = 15.0; // Hz
+
+pub fn update_camera_shake(time: Res<Time>, query: Query<(&mut CameraShake, &mut Transform)>) {
+    let t: f32 = time.elapsed_secs() * CAMERA_SHAKE_FREQUENCY * 2. * PI;
+
+    for (mut shake, mut transform) in query {
+        // remove last frame's offset so the rest position never drifts
+        transform.translation -= shake.offset;
+
+        // incommensurate phases keep the motion from looking like a circle
+        let offset: Vec3 = shake.amplitude * Vec3::new(t.sin(), (1.3 * t).sin(), (0.7 * t).cos());
+
+        transform.translation += offset;
+        shake.offset = offset;
+        shake.amplitude = Vec3::ZERO;
+    }
+}
+
+const CLEAR_FOG_COLOR: Color = Color::srgb(0.08, 0.10, 0.15);
+const CLEAR_FOG_START: f32 = 2_000.0; // m
+const CLEAR_FOG_END: f32 = 20_000.0; // m
+
+const CLOUD_FOG_COLOR: Color = Color::srgb(0.75, 0.77, 0.8);
+const CLOUD_FOG_END: f32 = 80.0; // m, visibility in the thickest cloud
+const CLOUD_FOG_RATE: f32 = 4.0; // 1/s
+
+// closes the fog in around the player while the plane is inside cloud
+pub fn update_cloud_fog(
+    time: Res<Time>,
+    plane: Single<Option<&InCloud>, (With<Player>, With<Plane>)>,
+    fog_query: Query<&mut DistanceFog, (With<Player>, Without<Plane>)>,
+) {
+    let density: f32 = plane.map_or(0., |cloud| cloud.density);
+
+    let color: Color = CLEAR_FOG_COLOR.mix(&CLOUD_FOG_COLOR, density);
+    let start: f32 = CLEAR_FOG_START * (1.0 - density);
+    let end: f32 = CLEAR_FOG_END.lerp(CLOUD_FOG_END, density);
+
+    // exponential approach, so entering and leaving cloud isn't a hard cut
+    let t: f32 = 1.0 - (-CLOUD_FOG_RATE * time.delta_secs()).exp();
+
+    for mut fog in fog_query {
+        fog.color = fog.color.mix(&color, t);
+
+        if let FogFalloff::Linear {
+            start: fog_start,
+            end: fog_end,
+        } = &mut fog.falloff
+        {
+            *fog_start = fog_start.lerp(start, t);
+            *fog_end = fog_end.lerp(end, t);
+        }
+    }
+}
 
 pub fn set_up_player_camera(
     commands: &mut Commands,
@@ -122,10 +182,10 @@ pub fn set_up_player_camera(
                 Player,
                 camera,
                 DistanceFog {
-                    color: Color::srgb(0.08, 0.10, 0.15),
+                    color: CLEAR_FOG_COLOR,
                     falloff: FogFalloff::Linear {
-                        start: 2_000.0,
-                        end: 20_000.0,
+                        start: CLEAR_FOG_START,
+                        end: CLEAR_FOG_END,
                     },
                     ..default()
                 },
@@ -323,11 +383,13 @@ pub fn visualize_gs(
                     .project_onto(*plane_global_transform.up())
                     .length()
                     .abs();
-                (0.5 * angular_velocity.x.powf(2.) * vertical_dist).abs()
-                    + -1.
-                        * angular_velocity.z.signum()
+                // q is nose up positive, the body z axis points out of the left wing
+                let (p, q, _) = body_rates(angular_velocity.0);
+
+                (0.5 * p.powf(2.) * vertical_dist).abs()
+                    - q.signum()
                         * 0.25
-                        * angular_velocity.z.powf(2.)
+                        * q.powf(2.)
                         * forward_dist
                         * 0.75
             }
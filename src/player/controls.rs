@@ -26,13 +26,14 @@ use crate::{
     cf104::{
         CanopyDoor, CanopyDoorHandle, Joystick, RotRange2D,
         console::{
-            RotRange,
+            RotRange, Toggle,
+            gear_lever::GearLever,
             radio::{RadioFxSelector, RadioVolume, UpdateRadioFx, UpdateVolume},
             throttle::Throttle,
         },
     },
     player::{Focused, Player, Selectable, Selected, camera::OutlineCamera},
-    projectile::{BrakeForce, Grounded, Projectile, SteeringWheel},
+    projectile::{Grounded, Projectile, SteeringWheel, brakes::Brakes},
 };
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -154,6 +155,8 @@ impl ArmBinding {
 pub struct FeetBinding {
     pub left: KeyBinding,
     pub right: KeyBinding,
+    pub left_brake: KeyBinding,
+    pub right_brake: KeyBinding,
 }
 
 impl FeetBinding {
@@ -164,6 +167,12 @@ impl FeetBinding {
         if self.right.key == key_code {
             self.right.state = KeyState::Pressed;
         }
+        if self.left_brake.key == key_code {
+            self.left_brake.state = KeyState::Pressed;
+        }
+        if self.right_brake.key == key_code {
+            self.right_brake.state = KeyState::Pressed;
+        }
     }
 
     pub fn released(&mut self, key_code: KeyCode) {
@@ -173,6 +182,12 @@ impl FeetBinding {
         if self.right.key == key_code {
             self.right.state = KeyState::Released;
         }
+        if self.left_brake.key == key_code {
+            self.left_brake.state = KeyState::Released;
+        }
+        if self.right_brake.key == key_code {
+            self.right_brake.state = KeyState::Released;
+        }
     }
 
     pub fn update(&mut self) {
@@ -188,6 +203,18 @@ impl FeetBinding {
             KeyState::Released => KeyState::None,
             KeyState::None => KeyState::None,
         };
+        self.left_brake.state = match self.left_brake.state {
+            KeyState::Pressed => KeyState::Held,
+            KeyState::Held => KeyState::Held,
+            KeyState::Released => KeyState::None,
+            KeyState::None => KeyState::None,
+        };
+        self.right_brake.state = match self.right_brake.state {
+            KeyState::Pressed => KeyState::Held,
+            KeyState::Held => KeyState::Held,
+            KeyState::Released => KeyState::None,
+            KeyState::None => KeyState::None,
+        };
     }
 }
 
@@ -221,6 +248,8 @@ impl Default for KeyBindings {
             feet: FeetBinding {
                 left: KeyBinding::new(KeyCode::KeyC),
                 right: KeyBinding::new(KeyCode::KeyN),
+                left_brake: KeyBinding::new(KeyCode::KeyX),
+                right_brake: KeyBinding::new(KeyCode::KeyM),
             },
             zoom: KeyBinding::new(KeyCode::Space),
         }
@@ -358,9 +387,13 @@ pub fn select_tool(
 
 pub fn grounded_controller(
     keybindings: Res<KeyBindings>,
-    mut plane: Single<(&mut BrakeForce, &mut SteeringWheel), (With<Player>, With<Grounded>)>,
+    time: Res<Time>,
+    mut plane: Single<(&mut Brakes, &mut SteeringWheel), (With<Player>, With<Grounded>)>,
 ) {
-    let (brake_force, wheel) = &mut *plane;
+    const TOE_PRESS_SPEED: f32 = 1.0; // pedal travel per s while held
+    const TOE_RELEASE_SPEED: f32 = 3.0;
+
+    let (brakes, wheel) = &mut *plane;
 
     let left_pressed = matches!(
         keybindings.feet.left.state,
@@ -371,25 +404,30 @@ pub fn grounded_controller(
         KeyState::Pressed | KeyState::Held
     );
 
-    match (left_pressed, right_pressed) {
-        (true, true) => {
-            // Both pedals pressed: full brake
-            brake_force.1 = true;
-            wheel.input_dir = 0.0;
-        }
-        (true, false) => {
-            brake_force.1 = false;
-            wheel.input_dir = -1.0;
-        }
-        (false, true) => {
-            brake_force.1 = false;
-            wheel.input_dir = 1.0;
-        }
-        _ => {
-            brake_force.1 = false;
-            wheel.input_dir = 0.0;
-        }
-    }
+    wheel.input_dir = match (left_pressed, right_pressed) {
+        (true, false) => -1.0,
+        (false, true) => 1.0,
+        _ => 0.0,
+    };
+
+    // toe brakes, the pedal goes down further the longer it is held
+    let left_brake = matches!(
+        keybindings.feet.left_brake.state,
+        KeyState::Pressed | KeyState::Held
+    );
+    let right_brake = matches!(
+        keybindings.feet.right_brake.state,
+        KeyState::Pressed | KeyState::Held
+    );
+
+    let delta_time = time.delta_secs();
+    let toe = |input: f32, held: bool| match held {
+        true => (input + TOE_PRESS_SPEED * delta_time).min(1.0),
+        false => (input - TOE_RELEASE_SPEED * delta_time).max(0.0),
+    };
+
+    brakes.left.input = toe(brakes.left.input, left_brake);
+    brakes.right.input = toe(brakes.right.input, right_brake);
 }
 
 pub fn throttle_controller(
@@ -645,3 +683,63 @@ pub fn radio_volume_controller(
         }
     }
 }
+
+pub fn toggle_switch_controller(
+    arms: Res<Arms>,
+    keybindings: Res<KeyBindings>,
+    switches: Query<(Entity, &mut Toggle), With<Selected>>,
+) {
+    for (entity, mut toggle) in switches {
+        let (left, right) = (arms.0 == Some(entity), arms.1 == Some(entity));
+        match (
+            (
+                left,
+                keybindings.left_arm.up.state,
+                keybindings.left_arm.down.state,
+            ),
+            (
+                right,
+                keybindings.right_arm.up.state,
+                keybindings.right_arm.down.state,
+            ),
+        ) {
+            ((true, KeyState::Pressed, _), _) | (_, (true, KeyState::Pressed, _)) => {
+                toggle.0 = true;
+            }
+            ((true, _, KeyState::Pressed), _) | (_, (true, _, KeyState::Pressed)) => {
+                toggle.0 = false;
+            }
+            _ => {}
+        }
+    }
+}
+
+pub fn gear_lever_controller(
+    arms: Res<Arms>,
+    keybindings: Res<KeyBindings>,
+    gear_levers: Query<(Entity, &mut GearLever), With<Selected>>,
+) {
+    for (entity, mut lever) in gear_levers {
+        let (left, right) = (arms.0 == Some(entity), arms.1 == Some(entity));
+        match (
+            (
+                left,
+                keybindings.left_arm.up.state,
+                keybindings.left_arm.down.state,
+            ),
+            (
+                right,
+                keybindings.right_arm.up.state,
+                keybindings.right_arm.down.state,
+            ),
+        ) {
+            ((true, KeyState::Pressed, _), _) | (_, (true, KeyState::Pressed, _)) => {
+                lever.0 = false;
+            }
+            ((true, _, KeyState::Pressed), _) | (_, (true, _, KeyState::Pressed)) => {
+                lever.0 = true;
+            }
+            _ => {}
+        }
+    }
+}
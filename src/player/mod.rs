@@ -3,12 +3,12 @@ use bevy::prelude::*;
 use crate::player::{
     camera::{
         MaskMaterials, OutlineCamera, OutlineTexture, look_camera, setup_mask_materials,
-        update_fov, visualize_gs,
+        update_camera_shake, update_cloud_fog, update_fov, visualize_gs,
     },
     controls::{
-        Arms, KeyBindings, canopy_door_controller, grounded_controller, joystick_controller,
-        radio_fx_controller, radio_volume_controller, select_tool, throttle_controller,
-        update_key_bindings,
+        Arms, KeyBindings, canopy_door_controller, gear_lever_controller, grounded_controller,
+        joystick_controller, radio_fx_controller, radio_volume_controller, select_tool,
+        throttle_controller, toggle_switch_controller, update_key_bindings,
     },
     ui::{center_cursor, fullscreen_startup, set_up_ui},
 };
@@ -39,6 +39,8 @@ impl Plugin for PlayerPlugin {
                     look_camera,
                     visualize_gs,
                     update_fov,
+                    update_camera_shake,
+                    update_cloud_fog,
                     center_cursor,
                     check_camera_selection,
                     select_tool,
@@ -49,6 +51,8 @@ impl Plugin for PlayerPlugin {
                     canopy_door_controller,
                     radio_fx_controller,
                     radio_volume_controller,
+                    toggle_switch_controller,
+                    gear_lever_controller,
                 ),
             );
     }
@@ -0,0 +1,171 @@
+use bevy::{
+    ecs::{
+        component::Component,
+        query::With,
+        system::{Query, Res},
+    },
+    math::{Quat, Vec3},
+    transform::components::Transform,
+};
+
+use crate::{
+    projectile::{
+        Projectile, Velocity,
+        atmosphere::AtmosphereSample,
+        turbulence::Turbulence,
+        util::{BODY_FORWARD, BODY_RIGHT, BODY_UP, altitude, speed_of_sound},
+        weather::{
+            Humidity, Pressure, PressureLevels, SimulationClock, Temperature, WeatherMeta, Wind,
+            get_wind, sample_atmosphere,
+        },
+    },
+    world::{
+        GlobalPosition,
+        util::{get_lat_f32, get_lon_f32},
+    },
+};
+
+#[derive(Component, Debug)]
+pub struct AirData {
+    pub temperature: f32, // K
+    pub pressure: f32,    // Pa
+    pub density: f32,     // kg/m^3
+    pub speed_of_sound: f32,
+
+    pub wind: Vec3,     // mean wind + gust (world space)
+    pub airspeed: Vec3, // air relative velocity (world space)
+    pub true_airspeed: f32,
+    pub mach: f32,
+    pub dynamic_pressure: f32,
+
+    pub alpha: f32, // rad
+    pub beta: f32,  // rad
+}
+
+impl Default for AirData {
+    fn default() -> Self {
+        Self {
+            temperature: 288.15,
+            pressure: 101_325.0,
+            density: 1.225,
+            speed_of_sound: 340.3,
+            wind: Vec3::ZERO,
+            airspeed: Vec3::ZERO,
+            true_airspeed: 0.,
+            mach: 0.,
+            dynamic_pressure: 0.,
+            alpha: 0.,
+            beta: 0.,
+        }
+    }
+}
+
+#[inline]
+pub fn angle_of_attack(rotation: Quat, airspeed: Vec3) -> f32 {
+    let body_airspeed = rotation.inverse() * airspeed;
+
+    let forward: f32 = body_airspeed.dot(BODY_FORWARD);
+    let down: f32 = -body_airspeed.dot(BODY_UP);
+
+    if forward.abs() < 1e-3 && down.abs() < 1e-3 {
+        return 0.;
+    }
+
+    down.atan2(forward)
+}
+
+#[inline]
+pub fn sideslip(rotation: Quat, airspeed: Vec3) -> f32 {
+    let speed: f32 = airspeed.length();
+
+    if speed < 1e-3 {
+        return 0.;
+    }
+
+    let body_airspeed = rotation.inverse() * airspeed;
+
+    (body_airspeed.dot(BODY_RIGHT) / speed)
+        .clamp(-1.0, 1.0)
+        .asin()
+}
+
+pub fn update_air_data(
+    //weather data
+    clock: Res<SimulationClock>,
+    weather_meta: Res<WeatherMeta>,
+    temperature: Res<Temperature>,
+    pressure: Res<Pressure>,
+    humidity: Res<Humidity>,
+    pressure_levels: Res<PressureLevels>,
+    wind: Res<Wind>,
+
+    mut query: Query<
+        (
+            &mut AirData,
+            &Velocity,
+            &GlobalPosition,
+            &Transform,
+            Option<&Turbulence>,
+        ),
+        With<Projectile>,
+    >,
+) {
+    let now: f64 = clock.now();
+
+    for (mut air_data, velocity, position, transform, turbulence) in &mut query {
+        // positional_data
+        let lat: f32 = get_lat_f32(position.x as f32);
+        let lon: f32 = get_lon_f32(position.z as f32);
+        let altitude: f32 = altitude(position.y as f32);
+
+        // weather data
+        let AtmosphereSample {
+            temperature,
+            pressure,
+            density,
+            virtual_temperature,
+        } = sample_atmosphere(
+            lat,
+            lon,
+            altitude,
+            now,
+            &weather_meta,
+            &temperature,
+            &pressure,
+            &humidity,
+            &pressure_levels,
+        );
+        let speed_of_sound: f32 = speed_of_sound(virtual_temperature);
+
+        // u -> east (+z), v -> north (+x); wind profile is above ground (world y = 0 at Lahr)
+        let (u, v) = get_wind(
+            lat,
+            lon,
+            (position.y as f32).max(0.),
+            now,
+            &weather_meta,
+            &wind,
+            &pressure_levels,
+        );
+        let gust: Vec3 = turbulence.map_or(Vec3::ZERO, |turbulence| turbulence.gust);
+        let wind: Vec3 = Vec3::new(v, 0., u) + gust;
+
+        // --- Air relative motion ---
+        let airspeed: Vec3 = velocity.0 - wind;
+        let true_airspeed: f32 = airspeed.length();
+
+        air_data.temperature = temperature;
+        air_data.pressure = pressure;
+        air_data.density = density;
+        air_data.speed_of_sound = speed_of_sound;
+
+        air_data.wind = wind;
+        air_data.airspeed = airspeed;
+        air_data.true_airspeed = true_airspeed;
+        air_data.mach = true_airspeed / speed_of_sound;
+        air_data.dynamic_pressure = 0.5 * density * true_airspeed * true_airspeed;
+
+        air_data.alpha = angle_of_attack(transform.rotation, airspeed);
+        air_data.beta = sideslip(transform.rotation, airspeed);
+    }
+}
@@ -0,0 +1,225 @@
+use crate::projectile::util::GAS_CONSTANT;
+
+// U.S. Standard Atmosphere 1976
+pub const ISA_GRAVITY: f32 = 9.80665; // m/s^2
+pub const ISA_EARTH_RADIUS: f32 = 6_356_766.0; // m
+pub const ISA_SEA_LEVEL_TEMPERATURE: f32 = 288.15; // K
+pub const ISA_SEA_LEVEL_PRESSURE: f32 = 101_325.0; // Pa
+pub const ISA_TOP: f32 = 84_852.0; // m, geopotential
+
+const VAPOR_GAS_CONSTANT: f32 = 461.5; // J/(kg K)
+pub const TROPOPAUSE: f32 = 11_000.0; // m, geopotential, no water vapor above
+
+// (base geopotential altitude m, lapse rate K/m)
+const ISA_LAYERS: [(f32, f32); 7] = [
+    (0.0, -0.0065),
+    (11_000.0, 0.0),
+    (20_000.0, 0.001),
+    (32_000.0, 0.0028),
+    (47_000.0, 0.0),
+    (51_000.0, -0.0028),
+    (71_000.0, -0.002),
+];
+
+#[inline]
+pub fn geopotential_altitude(altitude: f32) -> f32 {
+    ISA_EARTH_RADIUS * altitude / (ISA_EARTH_RADIUS + altitude)
+}
+
+#[inline]
+pub fn geometric_altitude(geopotential: f32) -> f32 {
+    ISA_EARTH_RADIUS * geopotential / (ISA_EARTH_RADIUS - geopotential)
+}
+
+// Tetens, over water
+#[inline]
+pub fn saturation_vapor_pressure(temperature: f32) -> f32 {
+    let celsius: f32 = temperature - 273.15;
+
+    610.78 * (17.27 * celsius / (celsius + 237.3)).exp()
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct AtmosphereSample {
+    pub temperature: f32,         // K
+    pub pressure: f32,            // Pa
+    pub density: f32,             // kg/m^3, moist air
+    pub virtual_temperature: f32, // K, dry air with the same density
+}
+
+impl AtmosphereSample {
+    // water vapor is lighter than dry air, moist air is less dense
+    pub fn new(temperature: f32, pressure: f32, relative_humidity: f32) -> Self {
+        let vapor_pressure: f32 = (relative_humidity.clamp(0.0, 1.0)
+            * saturation_vapor_pressure(temperature))
+        .min(pressure);
+
+        let epsilon: f32 = GAS_CONSTANT / VAPOR_GAS_CONSTANT;
+        let virtual_temperature: f32 =
+            temperature / (1.0 - vapor_pressure / pressure * (1.0 - epsilon));
+
+        Self {
+            temperature,
+            pressure,
+            density: pressure / (GAS_CONSTANT * virtual_temperature),
+            virtual_temperature,
+        }
+    }
+}
+
+// ISA with a constant temperature deviation, sea level pressure and surface humidity
+#[derive(Debug, Clone, Copy)]
+pub struct Atmosphere {
+    pub temperature_offset: f32, // K, ISA + dT
+    pub sea_level_pressure: f32, // Pa
+    pub relative_humidity: f32,  // 0..1, held up to the tropopause
+}
+
+impl Default for Atmosphere {
+    fn default() -> Self {
+        Self::standard()
+    }
+}
+
+impl Atmosphere {
+    pub fn standard() -> Self {
+        Self {
+            temperature_offset: 0.,
+            sea_level_pressure: ISA_SEA_LEVEL_PRESSURE,
+            relative_humidity: 0.,
+        }
+    }
+
+    // layer index, base temperature and base pressure for a geopotential altitude
+    fn layer(&self, geopotential: f32) -> (usize, f32, f32) {
+        let mut temperature: f32 = ISA_SEA_LEVEL_TEMPERATURE + self.temperature_offset;
+        let mut pressure: f32 = self.sea_level_pressure;
+
+        for i in 0..ISA_LAYERS.len() - 1 {
+            let (base, lapse_rate) = ISA_LAYERS[i];
+            let top: f32 = ISA_LAYERS[i + 1].0;
+
+            if geopotential < top {
+                return (i, temperature, pressure);
+            }
+
+            let top_temperature: f32 = temperature + lapse_rate * (top - base);
+            pressure = layer_pressure(pressure, temperature, lapse_rate, top - base);
+            temperature = top_temperature;
+        }
+
+        (ISA_LAYERS.len() - 1, temperature, pressure)
+    }
+
+    // geometric altitude above MSL (m), clamped to the top of the model
+    pub fn sample(&self, altitude: f32) -> AtmosphereSample {
+        let geopotential: f32 = geopotential_altitude(altitude.max(-1_000.0)).min(ISA_TOP);
+
+        let (i, base_temperature, base_pressure) = self.layer(geopotential);
+        let (base, lapse_rate) = ISA_LAYERS[i];
+
+        let temperature: f32 = base_temperature + lapse_rate * (geopotential - base);
+        let pressure: f32 = layer_pressure(
+            base_pressure,
+            base_temperature,
+            lapse_rate,
+            geopotential - base,
+        );
+
+        // --- Humidity ---
+        let relative_humidity: f32 = match geopotential < TROPOPAUSE {
+            true => self.relative_humidity,
+            false => 0.,
+        };
+
+        AtmosphereSample::new(temperature, pressure, relative_humidity)
+    }
+
+    pub fn temperature(&self, altitude: f32) -> f32 {
+        self.sample(altitude).temperature
+    }
+
+    pub fn pressure(&self, altitude: f32) -> f32 {
+        self.sample(altitude).pressure
+    }
+
+    pub fn density(&self, altitude: f32) -> f32 {
+        self.sample(altitude).density
+    }
+}
+
+// hydrostatic pressure at height dh above a layer base
+fn layer_pressure(base_pressure: f32, base_temperature: f32, lapse_rate: f32, dh: f32) -> f32 {
+    match lapse_rate == 0. {
+        true => base_pressure * (-ISA_GRAVITY * dh / (GAS_CONSTANT * base_temperature)).exp(),
+        false => {
+            base_pressure
+                * (1.0 + lapse_rate * dh / base_temperature)
+                    .powf(-ISA_GRAVITY / (GAS_CONSTANT * lapse_rate))
+        }
+    }
+}
+
+// standard atmosphere layer bases as (base, lapse rate, temperature, pressure)
+fn isa_layers() -> impl Iterator<Item = (f32, f32, f32, f32)> {
+    let standard = Atmosphere::standard();
+
+    ISA_LAYERS.iter().map(move |(base, lapse_rate)| {
+        let (_, temperature, pressure) = standard.layer(*base);
+
+        (*base, *lapse_rate, temperature, pressure)
+    })
+}
+
+// geometric altitude (m) at which the standard atmosphere has this pressure
+pub fn pressure_altitude(pressure: f32) -> f32 {
+    let (base, lapse_rate, temperature, base_pressure) = isa_layers()
+        .take_while(|(_, _, _, base_pressure)| *base_pressure >= pressure)
+        .last()
+        .unwrap_or((
+            0.0,
+            ISA_LAYERS[0].1,
+            ISA_SEA_LEVEL_TEMPERATURE,
+            ISA_SEA_LEVEL_PRESSURE,
+        ));
+
+    let ratio: f32 = pressure / base_pressure;
+
+    let geopotential: f32 = match lapse_rate == 0. {
+        true => base - GAS_CONSTANT * temperature / ISA_GRAVITY * ratio.ln(),
+        false => {
+            base + temperature / lapse_rate
+                * (ratio.powf(-GAS_CONSTANT * lapse_rate / ISA_GRAVITY) - 1.0)
+        }
+    };
+
+    geometric_altitude(geopotential)
+}
+
+// geometric altitude (m) at which the standard atmosphere has this density
+pub fn density_altitude(density: f32) -> f32 {
+    let (base, lapse_rate, temperature, base_pressure) = isa_layers()
+        .take_while(|(_, _, temperature, base_pressure)| {
+            base_pressure / (GAS_CONSTANT * temperature) >= density
+        })
+        .last()
+        .unwrap_or((
+            0.0,
+            ISA_LAYERS[0].1,
+            ISA_SEA_LEVEL_TEMPERATURE,
+            ISA_SEA_LEVEL_PRESSURE,
+        ));
+
+    let ratio: f32 = density / (base_pressure / (GAS_CONSTANT * temperature));
+
+    let geopotential: f32 = match lapse_rate == 0. {
+        true => base - GAS_CONSTANT * temperature / ISA_GRAVITY * ratio.ln(),
+        false => {
+            // rho / rho_b = (T / T_b)^(-g / (R L) - 1)
+            let exponent: f32 = -ISA_GRAVITY / (GAS_CONSTANT * lapse_rate) - 1.0;
+            base + temperature / lapse_rate * (ratio.powf(1.0 / exponent) - 1.0)
+        }
+    };
+
+    geometric_altitude(geopotential)
+}
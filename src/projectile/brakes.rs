@@ -0,0 +1,155 @@
+use bevy::{
+    ecs::{
+        component::Component,
+        query::{With, Without},
+        system::{Query, Res},
+    },
+    log::warn,
+    time::Time,
+};
+
+use crate::projectile::{
+    Crashed, Projectile, air_data::AirData, landing_gear::LandingGear, util::celsius_to_kelvin,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BrakeSide {
+    Left,
+    Right,
+}
+
+#[derive(Debug, Default)]
+pub struct Brake {
+    pub input: f32,    // 0..1 toe brake demand
+    pub pressure: f32, // 0..1
+
+    pub temperature: f32, // C, heat sink
+    pub fire: bool,       // burnt out, no friction left
+    pub tire_failed: bool,
+}
+
+#[derive(Component, Debug)]
+pub struct Brakes {
+    pub max_force: f32,  // N per wheel at full pressure
+    pub apply_rate: f32, // pressure per s
+
+    // thermal
+    pub heat_capacity: f32,         // J/K per brake
+    pub cooling: f32,               // W/K
+    pub fade_temperature: f32,      // C, onset of fade
+    pub fade_limit: f32,            // C, fully faded
+    pub fuse_plug_temperature: f32, // C, tire deflates
+    pub fire_temperature: f32,      // C
+
+    pub left: Brake,
+    pub right: Brake,
+}
+
+impl Brakes {
+    pub fn cf_104() -> Self {
+        Self {
+            max_force: 15_000.0,
+            apply_rate: 3.0,
+
+            heat_capacity: 20_000.0,
+            cooling: 60.0,
+            fade_temperature: 400.0,
+            fade_limit: 700.0,
+            fuse_plug_temperature: 550.0,
+            fire_temperature: 750.0,
+
+            left: Brake {
+                temperature: 15.,
+                ..Brake::default()
+            },
+            right: Brake {
+                temperature: 15.,
+                ..Brake::default()
+            },
+        }
+    }
+
+    pub fn get(&self, side: BrakeSide) -> &Brake {
+        match side {
+            BrakeSide::Left => &self.left,
+            BrakeSide::Right => &self.right,
+        }
+    }
+
+    pub fn get_mut(&mut self, side: BrakeSide) -> &mut Brake {
+        match side {
+            BrakeSide::Left => &mut self.left,
+            BrakeSide::Right => &mut self.right,
+        }
+    }
+
+    // hot linings lose up to half their friction
+    pub fn effectiveness(&self, side: BrakeSide) -> f32 {
+        if self.get(side).fire {
+            return 0.;
+        }
+
+        let fade: f32 = ((self.get(side).temperature - self.fade_temperature)
+            / (self.fade_limit - self.fade_temperature))
+            .clamp(0.0, 1.0);
+
+        1.0 - 0.5 * fade
+    }
+
+    // longitudinal force the brake asks of the tire (N)
+    pub fn demand(&self, side: BrakeSide) -> f32 {
+        self.max_force * self.get(side).pressure * self.effectiveness(side)
+    }
+}
+
+pub fn update_brakes(
+    time: Res<Time>,
+    mut query: Query<
+        (&mut Brakes, &mut LandingGear, &AirData),
+        (With<Projectile>, Without<Crashed>),
+    >,
+) {
+    let dt: f32 = time.delta_secs();
+
+    for (mut brakes, mut gear, air_data) in &mut query {
+        let ambient: f32 = air_data.temperature - celsius_to_kelvin(0.);
+
+        let apply_rate: f32 = brakes.apply_rate * dt;
+        let heat_capacity: f32 = brakes.heat_capacity;
+        let cooling: f32 = brakes.cooling;
+        let fuse_plug_temperature: f32 = brakes.fuse_plug_temperature;
+        let fire_temperature: f32 = brakes.fire_temperature;
+
+        for strut in &mut gear.struts {
+            let Some(side) = strut.brake else {
+                continue;
+            };
+            let brake: &mut Brake = brakes.get_mut(side);
+
+            // --- Pressure ---
+            brake.pressure += (brake.input - brake.pressure).clamp(-apply_rate, apply_rate);
+
+            // --- Heat ---
+            // all the work done by the brake ends up in the heat sink
+            let heat_in: f32 = strut.brake_power;
+            let heat_out: f32 = cooling * (brake.temperature - ambient);
+
+            brake.temperature += (heat_in - heat_out) / heat_capacity * dt;
+
+            // --- Failures ---
+            if !brake.tire_failed && brake.temperature > fuse_plug_temperature {
+                brake.tire_failed = true;
+                strut.tire.deflated = true;
+                warn!("{side:?} main tire fuse plugs melted, tire deflated");
+            }
+
+            // the fire burns the tire too
+            if !brake.fire && brake.temperature > fire_temperature {
+                brake.fire = true;
+                brake.tire_failed = true;
+                strut.tire.deflated = true;
+                warn!("{side:?} brake fire, the brake has failed");
+            }
+        }
+    }
+}
@@ -0,0 +1,192 @@
+use bevy::ecs::{
+    component::Component,
+    entity::Entity,
+    query::{With, Without},
+    system::{Commands, Query, Res},
+};
+
+use crate::{
+    projectile::{
+        Crashed, Projectile,
+        util::altitude,
+        weather::{
+            CloudCover, Humidity, SimulationClock, Temperature, WeatherMeta, get_cloud_base,
+            get_cloud_cover,
+        },
+    },
+    world::{
+        GlobalPosition,
+        util::{FIELD_ELEVATION_F32, get_lat_f32, get_lon_f32},
+    },
+};
+
+const CELL_SIZE: f64 = 2_000.0; // m, horizontal size of the cloud patches
+const EDGE: f32 = 50.0; // m, fade in at the cloud edges
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CloudLevel {
+    Low,
+    Mid,
+    High,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct CloudLayer {
+    pub level: CloudLevel,
+    pub cover: f32, // 0..1
+    pub base: f32,  // m above MSL
+    pub top: f32,   // m above MSL
+}
+
+impl CloudLayer {
+    // 0..1, how far inside cloud a point is, partial cover breaks the layer into patches
+    pub fn density(&self, x: f64, z: f64, altitude: f32) -> f32 {
+        if self.cover <= 0. || altitude <= self.base || altitude >= self.top {
+            return 0.;
+        }
+
+        let vertical: f32 = ((altitude - self.base).min(self.top - altitude) / EDGE).min(1.0);
+
+        let noise: f32 = value_noise(x / CELL_SIZE, z / CELL_SIZE, self.level as u64);
+        let horizontal: f32 = ((self.cover - noise) / 0.1).clamp(0.0, 1.0);
+
+        vertical * horizontal
+    }
+}
+
+// low cloud sits on the condensation level, mid and high on the usual ERA5 bands
+pub fn get_cloud_layers(
+    lat: f32,
+    lon: f32,
+    time: f64,
+    meta: &Res<WeatherMeta>,
+    cloud_cover: &Res<CloudCover>,
+    temperature: &Res<Temperature>,
+    humidity: &Res<Humidity>,
+) -> [CloudLayer; 3] {
+    const DEFAULT_LOW_BASE: f32 = 900.0; // m above the field
+
+    let (low, mid, high) = get_cloud_cover(lat, lon, time, meta, cloud_cover);
+    let (low, mid, high) = (
+        low.clamp(0.0, 1.0),
+        mid.clamp(0.0, 1.0),
+        high.clamp(0.0, 1.0),
+    );
+
+    let low_base: f32 = FIELD_ELEVATION_F32
+        + get_cloud_base(lat, lon, time, meta, temperature, humidity)
+            .unwrap_or(DEFAULT_LOW_BASE)
+            .clamp(150.0, 2_000.0);
+    let low_top: f32 = low_base + 300.0 + 1_200.0 * low;
+
+    let mid_base: f32 = low_top.max(3_000.0);
+    let mid_top: f32 = mid_base + 500.0 + 1_500.0 * mid;
+
+    let high_base: f32 = mid_top.max(7_000.0);
+    let high_top: f32 = high_base + 500.0 + 1_000.0 * high;
+
+    [
+        CloudLayer {
+            level: CloudLevel::Low,
+            cover: low,
+            base: low_base,
+            top: low_top,
+        },
+        CloudLayer {
+            level: CloudLevel::Mid,
+            cover: mid,
+            base: mid_base,
+            top: mid_top,
+        },
+        CloudLayer {
+            level: CloudLevel::High,
+            cover: high,
+            base: high_base,
+            top: high_top,
+        },
+    ]
+}
+
+fn hash(x: i64, z: i64, seed: u64) -> f32 {
+    let mut h: u64 = (x as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+        ^ (z as u64).wrapping_mul(0xC2B2_AE3D_27D4_EB4F)
+        ^ seed.wrapping_mul(0x1656_67B1_9E37_79F9);
+
+    h ^= h >> 33;
+    h = h.wrapping_mul(0xFF51_AFD7_ED55_8CCD);
+    h ^= h >> 33;
+
+    (h >> 40) as f32 / (1u64 << 24) as f32
+}
+
+// smooth 0..1 noise on a unit grid
+fn value_noise(x: f64, z: f64, seed: u64) -> f32 {
+    let (x0, z0) = (x.floor(), z.floor());
+    let smooth = |t: f32| t * t * (3.0 - 2.0 * t);
+    let (tx, tz) = (smooth((x - x0) as f32), smooth((z - z0) as f32));
+    let (ix, iz) = (x0 as i64, z0 as i64);
+
+    let f0 = hash(ix, iz, seed) * (1.0 - tx) + hash(ix + 1, iz, seed) * tx;
+    let f1 = hash(ix, iz + 1, seed) * (1.0 - tx) + hash(ix + 1, iz + 1, seed) * tx;
+
+    f0 * (1.0 - tz) + f1 * tz
+}
+
+// present while inside cloud, for the fog, turbulence, instruments and missions
+#[derive(Component, Debug)]
+pub struct InCloud {
+    pub level: CloudLevel,
+    pub density: f32, // 0..1
+}
+
+pub fn update_in_cloud(
+    mut commands: Commands,
+
+    //weather data
+    clock: Res<SimulationClock>,
+    weather_meta: Res<WeatherMeta>,
+    cloud_cover: Res<CloudCover>,
+    temperature: Res<Temperature>,
+    humidity: Res<Humidity>,
+
+    mut query: Query<
+        (Entity, &GlobalPosition, Option<&mut InCloud>),
+        (With<Projectile>, Without<Crashed>),
+    >,
+) {
+    for (entity, position, in_cloud) in &mut query {
+        let lat: f32 = get_lat_f32(position.x as f32);
+        let lon: f32 = get_lon_f32(position.z as f32);
+        let altitude: f32 = altitude(position.y as f32);
+
+        let layers: [CloudLayer; 3] = get_cloud_layers(
+            lat,
+            lon,
+            clock.now(),
+            &weather_meta,
+            &cloud_cover,
+            &temperature,
+            &humidity,
+        );
+
+        let densest = layers
+            .iter()
+            .map(|layer| (layer.level, layer.density(position.x, position.z, altitude)))
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .filter(|(_, density)| *density > 0.);
+
+        match (densest, in_cloud) {
+            (Some((level, density)), Some(mut in_cloud)) => {
+                in_cloud.level = level;
+                in_cloud.density = density;
+            }
+            (Some((level, density)), None) => {
+                commands.entity(entity).insert(InCloud { level, density });
+            }
+            (None, Some(_)) => {
+                commands.entity(entity).remove::<InCloud>();
+            }
+            (None, None) => {}
+        }
+    }
+}
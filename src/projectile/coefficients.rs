@@ -0,0 +1,307 @@
+use bevy::{
+    asset::{Asset, AssetLoader, Handle, LoadContext, io::Reader},
+    ecs::component::Component,
+    reflect::TypePath,
+};
+use ron::de::SpannedError;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Table2D {
+    pub rows: Vec<f32>,    // angle (deg)
+    pub columns: Vec<f32>, // mach
+    pub values: Vec<f32>,
+}
+
+impl Table2D {
+    fn validate(&self, name: &'static str) -> Result<(), AeroCoefficientsLoaderError> {
+        validate_table(name, &[&self.rows, &self.columns], &self.values)
+    }
+
+    pub fn sample(&self, row: f32, column: f32) -> f32 {
+        bilinear(&self.rows, &self.columns, &self.values, row, column)
+    }
+}
+
+// one alpha x mach table per sideslip breakpoint
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Table3D {
+    pub rows: Vec<f32>,    // angle of attack (deg)
+    pub slices: Vec<f32>,  // sideslip (deg)
+    pub columns: Vec<f32>, // mach
+    pub values: Vec<f32>,  // [slice][row][column]
+}
+
+impl Table3D {
+    fn validate(&self, name: &'static str) -> Result<(), AeroCoefficientsLoaderError> {
+        validate_table(
+            name,
+            &[&self.rows, &self.slices, &self.columns],
+            &self.values,
+        )
+    }
+
+    pub fn sample(&self, row: f32, slice: f32, column: f32) -> f32 {
+        let (slice_idx, w) = bracket(&self.slices, slice);
+        let next_slice = (slice_idx + 1).min(self.slices.len() - 1);
+
+        let slice_len: usize = self.rows.len() * self.columns.len();
+        let slice_at = |k: usize| &self.values[k * slice_len..(k + 1) * slice_len];
+
+        let f0 = bilinear(&self.rows, &self.columns, slice_at(slice_idx), row, column);
+        let f1 = bilinear(&self.rows, &self.columns, slice_at(next_slice), row, column);
+
+        f0 * (1.0 - w) + f1 * w
+    }
+}
+
+fn validate_table(
+    name: &'static str,
+    axes: &[&Vec<f32>],
+    values: &[f32],
+) -> Result<(), AeroCoefficientsLoaderError> {
+    if axes.iter().any(|axis| axis.is_empty()) {
+        return Err(AeroCoefficientsLoaderError::EmptyTable(name));
+    }
+
+    let finite = |values: &[f32]| values.iter().all(|value| value.is_finite());
+    if !axes.iter().all(|axis| finite(axis)) || !finite(values) {
+        return Err(AeroCoefficientsLoaderError::NonFiniteTable(name));
+    }
+
+    let ascending = |axis: &[f32]| axis.windows(2).all(|pair| pair[0] < pair[1]);
+    if !axes.iter().all(|axis| ascending(axis)) {
+        return Err(AeroCoefficientsLoaderError::UnsortedTable(name));
+    }
+
+    let expected: usize = axes.iter().map(|axis| axis.len()).product();
+    if values.len() != expected {
+        return Err(AeroCoefficientsLoaderError::MismatchedTable {
+            name,
+            expected,
+            found: values.len(),
+        });
+    }
+
+    Ok(())
+}
+
+fn bilinear(rows: &[f32], columns: &[f32], values: &[f32], row: f32, column: f32) -> f32 {
+    let (row_idx, t) = bracket(rows, row);
+    let (column_idx, u) = bracket(columns, column);
+
+    let n_columns = columns.len();
+    let next_row = (row_idx + 1).min(rows.len() - 1);
+    let next_column = (column_idx + 1).min(n_columns - 1);
+
+    // --- Retrieve four corner values ---
+    let idx = |i, j| i * n_columns + j;
+    let f00 = values[idx(row_idx, column_idx)];
+    let f10 = values[idx(next_row, column_idx)];
+    let f01 = values[idx(row_idx, next_column)];
+    let f11 = values[idx(next_row, next_column)];
+
+    // --- Bilinear interpolation ---
+    let f0 = f00 * (1.0 - t) + f10 * t;
+    let f1 = f01 * (1.0 - t) + f11 * t;
+
+    f0 * (1.0 - u) + f1 * u
+}
+
+// index of the lower breakpoint and the normalized weight towards the next one,
+// NaN falls on the first breakpoint
+fn bracket(axis: &[f32], value: f32) -> (usize, f32) {
+    let n = axis.len();
+
+    if n < 2 || value.is_nan() {
+        return (0, 0.);
+    }
+
+    let value = value.clamp(axis[0], axis[n - 1]);
+
+    let idx = match axis.binary_search_by(|x| x.total_cmp(&value)) {
+        Ok(i) => i.min(n - 2),
+        Err(i) => i.saturating_sub(1).min(n - 2),
+    };
+
+    let denom = (axis[idx + 1] - axis[idx]).abs().max(f32::EPSILON);
+
+    (idx, (value - axis[idx]) / denom)
+}
+
+// stability and control derivatives: beta in rad, rates non-dimensional, surfaces -1..1
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AeroDerivatives {
+    pub span: f32,  // m
+    pub chord: f32, // m (mean aerodynamic chord)
+
+    // pitch
+    pub cm_q: f32,
+    pub cm_de: f32,
+
+    // roll
+    pub cl_beta: f32,
+    pub cl_p: f32,
+    pub cl_da: f32,
+
+    // yaw
+    pub cn_beta: f32,
+    pub cn_r: f32,
+    pub cn_dr: f32,
+
+    // side force
+    pub cy_dr: f32,
+}
+
+impl AeroDerivatives {
+    fn validate(&self) -> Result<(), AeroCoefficientsLoaderError> {
+        let values: [f32; 11] = [
+            self.span,
+            self.chord,
+            self.cm_q,
+            self.cm_de,
+            self.cl_beta,
+            self.cl_p,
+            self.cl_da,
+            self.cn_beta,
+            self.cn_r,
+            self.cn_dr,
+            self.cy_dr,
+        ];
+
+        if !values.iter().all(|value| value.is_finite()) || self.span <= 0. || self.chord <= 0. {
+            return Err(AeroCoefficientsLoaderError::InvalidDerivatives);
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Asset, TypePath, Debug, Serialize, Deserialize)]
+pub struct AeroCoefficients {
+    pub derivatives: AeroDerivatives,
+
+    pub cl: Table3D, // (alpha, beta, mach)
+    pub cd: Table3D, // (alpha, beta, mach)
+    pub cy: Table3D, // (alpha, beta, mach)
+    pub cm: Table3D, // (alpha, beta, mach)
+
+    // multipliers on the AeroDerivatives (|alpha|, mach)
+    pub aileron_effectiveness: Table2D,
+    pub rudder_effectiveness: Table2D,
+    pub roll_damping: Table2D,
+    pub yaw_damping: Table2D,
+}
+
+impl AeroCoefficients {
+    #[inline]
+    pub fn cl(&self, alpha: f32, beta: f32, mach: f32) -> f32 {
+        self.cl.sample(alpha.to_degrees(), beta.to_degrees(), mach)
+    }
+
+    #[inline]
+    pub fn cd(&self, alpha: f32, beta: f32, mach: f32) -> f32 {
+        self.cd.sample(alpha.to_degrees(), beta.to_degrees(), mach)
+    }
+
+    #[inline]
+    pub fn cy(&self, alpha: f32, beta: f32, mach: f32) -> f32 {
+        self.cy.sample(alpha.to_degrees(), beta.to_degrees(), mach)
+    }
+
+    #[inline]
+    pub fn cm(&self, alpha: f32, beta: f32, mach: f32) -> f32 {
+        self.cm.sample(alpha.to_degrees(), beta.to_degrees(), mach)
+    }
+
+    #[inline]
+    pub fn aileron_effectiveness(&self, alpha: f32, mach: f32) -> f32 {
+        self.aileron_effectiveness
+            .sample(alpha.abs().to_degrees(), mach)
+    }
+
+    #[inline]
+    pub fn rudder_effectiveness(&self, alpha: f32, mach: f32) -> f32 {
+        self.rudder_effectiveness
+            .sample(alpha.abs().to_degrees(), mach)
+    }
+
+    #[inline]
+    pub fn roll_damping(&self, alpha: f32, mach: f32) -> f32 {
+        self.roll_damping.sample(alpha.abs().to_degrees(), mach)
+    }
+
+    #[inline]
+    pub fn yaw_damping(&self, alpha: f32, mach: f32) -> f32 {
+        self.yaw_damping.sample(alpha.abs().to_degrees(), mach)
+    }
+}
+
+#[derive(Component, Debug)]
+pub struct AeroModel(pub Handle<AeroCoefficients>);
+
+#[derive(Debug, Error)]
+pub enum AeroCoefficientsLoaderError {
+    #[error("IO error while reading file: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Failed to parse RON config: {0}")]
+    Ron(#[from] SpannedError),
+
+    #[error("Derivatives must be finite, with a positive span and chord")]
+    InvalidDerivatives,
+
+    #[error("Table `{0}` has no breakpoints")]
+    EmptyTable(&'static str),
+
+    #[error("Table `{0}` has a non-finite breakpoint or value")]
+    NonFiniteTable(&'static str),
+
+    #[error("Table `{0}` breakpoints must be strictly ascending")]
+    UnsortedTable(&'static str),
+
+    #[error("Table `{name}` expected {expected} values, found {found}")]
+    MismatchedTable {
+        name: &'static str,
+        expected: usize,
+        found: usize,
+    },
+}
+
+#[derive(Default)]
+pub struct AeroCoefficientsLoader;
+
+impl AssetLoader for AeroCoefficientsLoader {
+    type Asset = AeroCoefficients;
+    type Settings = ();
+    type Error = AeroCoefficientsLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let data: AeroCoefficients = ron::de::from_bytes(&bytes)?;
+
+        data.derivatives.validate()?;
+        data.cl.validate("cl")?;
+        data.cd.validate("cd")?;
+        data.cy.validate("cy")?;
+        data.cm.validate("cm")?;
+        data.aileron_effectiveness
+            .validate("aileron_effectiveness")?;
+        data.rudder_effectiveness.validate("rudder_effectiveness")?;
+        data.roll_damping.validate("roll_damping")?;
+        data.yaw_damping.validate("yaw_damping")?;
+
+        Ok(data)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["aero"]
+    }
+}
@@ -0,0 +1,227 @@
+use bevy::{
+    ecs::{
+        component::Component,
+        entity::Entity,
+        message::{Message, MessageReader, MessageWriter},
+        query::{Has, With, Without},
+        system::{Commands, Query},
+    },
+    log::{info, warn},
+    math::{Quat, Vec3},
+    transform::components::Transform,
+};
+
+use crate::{
+    projectile::{
+        Crashed, Grounded, Projectile, Velocity,
+        landing_gear::LandingGear,
+        util::{BODY_FORWARD, BODY_RIGHT, BODY_UP},
+    },
+    world::{
+        GlobalPosition,
+        ground::Terrain,
+        util::{get_lat_f64, get_lon_f64},
+    },
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImpactKind {
+    SurvivableLanding,
+    HardLanding,
+    Crash,
+}
+
+impl ImpactKind {
+    // sink rate / ground speed / attitude limits, rates in m/s and angles in rad
+    pub fn classify(velocity: Vec3, rotation: Quat) -> Self {
+        let (pitch, bank) = attitude(rotation);
+
+        let sink_rate: f32 = -velocity.y;
+        let speed: f32 = velocity.length();
+
+        let level = |max_bank: f32| bank.abs() < max_bank && (-0.1..0.35).contains(&pitch);
+
+        if sink_rate < 3.0 && speed < 95.0 && level(10f32.to_radians()) {
+            Self::SurvivableLanding
+        } else if sink_rate < 6.0 && speed < 110.0 && level(30f32.to_radians()) {
+            Self::HardLanding
+        } else {
+            Self::Crash
+        }
+    }
+}
+
+// (pitch, bank) in rad, nose up and right wing down positive
+pub fn attitude(rotation: Quat) -> (f32, f32) {
+    let forward: Vec3 = rotation * BODY_FORWARD;
+    let up: Vec3 = rotation * BODY_UP;
+    let right: Vec3 = rotation * BODY_RIGHT;
+
+    let pitch: f32 = forward.y.clamp(-1.0, 1.0).asin();
+    let bank: f32 = (-right.y).atan2(up.y);
+
+    (pitch, bank)
+}
+
+#[derive(Message, Debug, Clone)]
+pub struct ImpactMessage {
+    pub entity: Entity,
+    pub kind: ImpactKind,
+
+    pub speed: f32,     // m/s
+    pub sink_rate: f32, // m/s
+    pub pitch: f32,     // rad
+    pub bank: f32,      // rad
+
+    pub position: GlobalPosition,
+    pub lat: f64,
+    pub lon: f64,
+}
+
+impl ImpactMessage {
+    pub fn new(
+        entity: Entity,
+        kind: ImpactKind,
+        velocity: Vec3,
+        rotation: Quat,
+        position: &GlobalPosition,
+    ) -> Self {
+        let (pitch, bank) = attitude(rotation);
+
+        Self {
+            entity,
+            kind,
+            speed: velocity.length(),
+            sink_rate: -velocity.y,
+            pitch,
+            bank,
+            position: *position,
+            lat: get_lat_f64(position.x),
+            lon: get_lon_f64(position.z),
+        }
+    }
+}
+
+// body space points that must stay above the terrain, the origin is used when absent
+#[derive(Component, Debug)]
+pub struct Hull(pub Vec<Vec3>);
+
+impl Hull {
+    pub fn cf_104() -> Self {
+        Self(vec![
+            Vec3::new(-8.0, -0.3, 0.),  // nose
+            Vec3::new(-2.0, -0.9, 0.),  // forward belly
+            Vec3::new(2.5, -0.9, 0.),   // aft belly
+            Vec3::new(8.0, -0.1, 0.),   // tail cone
+            Vec3::new(7.0, 3.3, 0.),    // fin tip
+            Vec3::new(1.0, -0.3, 3.4),  // left wing tip
+            Vec3::new(1.0, -0.3, -3.4), // right wing tip
+        ])
+    }
+}
+
+// the hull is resting on the terrain after a survivable impact
+#[derive(Component, Debug)]
+pub struct HullContact;
+
+pub fn update_terrain_collision(
+    terrain: Terrain,
+
+    mut commands: Commands,
+    mut impact_writer: MessageWriter<ImpactMessage>,
+    mut query: Query<
+        (
+            Entity,
+            &Transform,
+            &GlobalPosition,
+            &mut Velocity,
+            Option<&Hull>,
+            Has<LandingGear>,
+            Has<HullContact>,
+        ),
+        (With<Projectile>, Without<Crashed>),
+    >,
+) {
+    for (entity, transform, position, mut velocity, hull, aircraft, resting) in &mut query {
+        let points: &[Vec3] = match hull {
+            Some(hull) => &hull.0,
+            None => &[Vec3::ZERO],
+        };
+
+        let contact: bool = points.iter().any(|point| {
+            let offset: Vec3 = transform.rotation * *point;
+
+            let x: f64 = position.x + offset.x as f64;
+            let y: f64 = position.y + offset.y as f64;
+            let z: f64 = position.z + offset.z as f64;
+
+            // field level until the height data is loaded
+            let ground: f32 = terrain.height(x, z).unwrap_or(0.);
+
+            y <= ground as f64
+        });
+
+        if !contact {
+            if resting {
+                commands.entity(entity).remove::<HullContact>();
+            }
+            continue;
+        }
+
+        // dropped stores and debris just stop where they land
+        if !aircraft {
+            commands.entity(entity).insert(Crashed);
+            continue;
+        }
+
+        // sliding on the hull, only the first contact is an impact
+        if !resting {
+            let kind: ImpactKind = ImpactKind::classify(velocity.0, transform.rotation);
+
+            impact_writer.write(ImpactMessage::new(
+                entity,
+                kind,
+                velocity.0,
+                transform.rotation,
+                position,
+            ));
+
+            if kind == ImpactKind::Crash {
+                commands.entity(entity).insert(Crashed);
+                continue;
+            }
+
+            commands.entity(entity).insert((HullContact, Grounded));
+        }
+
+        // the terrain stops the hull from sinking any further
+        velocity.0.y = velocity.0.y.max(0.);
+    }
+}
+
+pub fn log_impacts(mut impact_reader: MessageReader<ImpactMessage>) {
+    for impact in impact_reader.read() {
+        let icon: &str = match impact.kind {
+            ImpactKind::SurvivableLanding => "🛬",
+            ImpactKind::HardLanding => "⚠️ ",
+            ImpactKind::Crash => "💥",
+        };
+
+        let message = format!(
+            "{icon} {:?} ({:?}) at {:.4}, {:.4}: {:.1} m/s, sink {:.1} m/s, pitch {:.1}°, bank {:.1}°",
+            impact.kind,
+            impact.entity,
+            impact.lat,
+            impact.lon,
+            impact.speed,
+            impact.sink_rate,
+            impact.pitch.to_degrees(),
+            impact.bank.to_degrees(),
+        );
+
+        match impact.kind {
+            ImpactKind::Crash => warn!("{message}"),
+            _ => info!("{message}"),
+        }
+    }
+}
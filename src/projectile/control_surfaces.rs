@@ -1,5 +1,7 @@
 use bevy::{
+    asset::Assets,
     ecs::{
+        component::Component,
         query::{With, Without},
         system::{Query, Res, Single},
     },
@@ -10,58 +12,155 @@ use bevy::{
 use crate::{
     cf104::Joystick,
     player::controls::{KeyBindings, KeyState},
-    projectile::{AngularVelocity, Grounded, Projectile, Velocity},
+    projectile::{
+        AeroMoment, AngularVelocity, Crashed, Projectile, WingArea,
+        air_data::AirData,
+        coefficients::{AeroCoefficients, AeroModel},
+        structure::Structure,
+        util::{BODY_FORWARD, BODY_RIGHT, BODY_UP},
+    },
 };
 
-pub fn update_angular_projectile_velocity(
+// Body axes (util.rs): roll about the nose (right wing down +), pitch about the right wing
+// (nose up +), yaw about the down axis (nose right +)
+#[inline]
+pub fn body_rates(angular_velocity: Vec3) -> (f32, f32, f32) {
+    let p = angular_velocity.dot(BODY_FORWARD);
+    let q = angular_velocity.dot(BODY_RIGHT);
+    let r = -angular_velocity.dot(BODY_UP);
+
+    (p, q, r)
+}
+
+#[inline]
+pub fn body_moment(roll: f32, pitch: f32, yaw: f32) -> Vec3 {
+    roll * BODY_FORWARD + pitch * BODY_RIGHT - yaw * BODY_UP
+}
+
+#[derive(Component, Debug)]
+pub struct ControlSurfaces {
+    pub aileron: f32,  // -1..1, right roll +
+    pub elevator: f32, // -1..1, nose up +
+    pub rudder: f32,   // -1..1, nose right +
+    pub delta_speed: f32,
+}
+
+impl Default for ControlSurfaces {
+    fn default() -> Self {
+        Self {
+            aileron: 0.,
+            elevator: 0.,
+            rudder: 0.,
+            delta_speed: 4.0,
+        }
+    }
+}
+
+pub fn update_control_surfaces(
+    time: Res<Time>,
     joystick: Single<&Joystick>,
     keybindings: Res<KeyBindings>,
-    mut query: Query<(&Velocity, &mut AngularVelocity), (With<Projectile>, Without<Grounded>)>,
+    mut query: Query<&mut ControlSurfaces, With<Projectile>>,
 ) {
-    const PITCH_RATE: f32 = 1.0;
-    const YAW_RATE: f32 = 0.1;
-    const ROLL_RATE: f32 = 2.;
-
-    for (velocity, mut ang_vel) in &mut query {
-        let input = joystick.0;
-
-        let pitch_input: f32 = input.y;
-        let roll_input: f32 = input.x;
-
-        // Pedal yaw
-        let left_pedal = keybindings.feet.left.state == KeyState::Held
-            || keybindings.feet.left.state == KeyState::Pressed;
-        let right_pedal = keybindings.feet.right.state == KeyState::Held
-            || keybindings.feet.right.state == KeyState::Pressed;
-
-        let yaw_input: f32 = match (left_pedal, right_pedal) {
-            (true, false) => -1.0,
-            (false, true) => 1.0,
-            _ => 0.0,
-        };
+    let dt: f32 = time.delta_secs();
 
-        let speed: f32 = velocity.length();
-        let speed_factor = 1.0 / (1.0 + speed * 0.01);
+    let input = joystick.0;
 
-        ang_vel.0 = Vec3::new(
-            roll_input * -ROLL_RATE * speed_factor,
-            yaw_input * -YAW_RATE * speed_factor,
-            pitch_input * PITCH_RATE * speed_factor,
-        );
+    // Pedal yaw
+    let left_pedal = keybindings.feet.left.state == KeyState::Held
+        || keybindings.feet.left.state == KeyState::Pressed;
+    let right_pedal = keybindings.feet.right.state == KeyState::Held
+        || keybindings.feet.right.state == KeyState::Pressed;
+
+    let yaw_input: f32 = match (left_pedal, right_pedal) {
+        (true, false) => -1.0,
+        (false, true) => 1.0,
+        _ => 0.0,
+    };
 
-        // println!("angular velocity: {:?}", ang_vel.0);
+    // stick right rolls right, stick forward (-y) pitches the nose down
+    let targets: Vec3 = Vec3::new(input.x, input.y, yaw_input);
+
+    for mut surfaces in &mut query {
+        let max_delta: f32 = surfaces.delta_speed * dt;
+
+        let current: Vec3 = Vec3::new(surfaces.aileron, surfaces.elevator, surfaces.rudder);
+        let delta: Vec3 =
+            (targets - current).clamp(Vec3::splat(-max_delta), Vec3::splat(max_delta));
+        let next: Vec3 = (current + delta).clamp(Vec3::NEG_ONE, Vec3::ONE);
+
+        surfaces.aileron = next.x;
+        surfaces.elevator = next.y;
+        surfaces.rudder = next.z;
     }
 }
 
-pub fn apply_angular_damping(
-    time: Res<Time>,
-    mut query: Query<(&Velocity, &mut AngularVelocity), With<Projectile>>,
+pub fn update_angular_projectile_velocity(
+    aero_assets: Res<Assets<AeroCoefficients>>,
+    mut query: Query<
+        (
+            &mut AeroMoment,
+            &AngularVelocity,
+            &AirData,
+            &ControlSurfaces,
+            &AeroModel,
+            &WingArea,
+            &Structure,
+        ),
+        (With<Projectile>, Without<Crashed>),
+    >,
 ) {
-    for (vel, mut ang_vel) in &mut query {
-        let airspeed = vel.length();
-        let damping_strength = airspeed / 200.0; // stronger at high speed
-        let damping = damping_strength.clamp(0.2, 3.0);
+    for (mut aero_moment, angular_velocity, air_data, surfaces, aero_model, wing_area, structure) in
+        &mut query
+    {
+        let Some(coefficients) = aero_assets.get(aero_model.0.id()) else {
+            continue;
+        };
+        let derivatives = &coefficients.derivatives;
+
+        let speed: f32 = air_data.true_airspeed;
+
+        if speed < 1e-3 {
+            aero_moment.0 = Vec3::ZERO;
+            continue;
+        }
 
-        ang_vel.0 *= 1.0 - (damping * time.delta_secs()).min(1.0);
+        let (p, q, r) = body_rates(angular_velocity.0);
+
+        // non-dimensional rates
+        let p_hat: f32 = p * derivatives.span / (2.0 * speed);
+        let q_hat: f32 = q * derivatives.chord / (2.0 * speed);
+        let r_hat: f32 = r * derivatives.span / (2.0 * speed);
+
+        // --- High alpha ---
+        // controls lose authority and the damping reverses past the stall
+        let (alpha, beta, mach) = (air_data.alpha, air_data.beta, air_data.mach);
+
+        let aileron_effectiveness: f32 = coefficients.aileron_effectiveness(alpha, mach);
+        let rudder_effectiveness: f32 = coefficients.rudder_effectiveness(alpha, mach);
+        let roll_damping: f32 = coefficients.roll_damping(alpha, mach);
+        let yaw_damping: f32 = coefficients.yaw_damping(alpha, mach);
+
+        // --- Coefficients ---
+        let c_roll: f32 = derivatives.cl_beta * beta
+            + derivatives.cl_da * surfaces.aileron * aileron_effectiveness
+            + derivatives.cl_p * p_hat * roll_damping
+            + structure.roll_asymmetry() * coefficients.cl(alpha, beta, mach);
+        let c_pitch: f32 = coefficients.cm(alpha, beta, mach)
+            + derivatives.cm_de * surfaces.elevator
+            + derivatives.cm_q * q_hat;
+        let c_yaw: f32 = derivatives.cn_beta * beta
+            + derivatives.cn_dr * surfaces.rudder * rudder_effectiveness
+            + derivatives.cn_r * r_hat * yaw_damping;
+
+        // --- Moments ---
+        // about the body origin, update_forces adds the offset of the forces from the cg
+        let q_s: f32 = air_data.dynamic_pressure * wing_area.0;
+
+        aero_moment.0 = body_moment(
+            q_s * derivatives.span * c_roll,
+            q_s * derivatives.chord * c_pitch,
+            q_s * derivatives.span * c_yaw,
+        );
     }
 }
@@ -1,26 +1,30 @@
-use std::array;
-
 use bevy::{
-    asset::Assets,
+    asset::{Asset, AssetId, Assets, Handle},
     ecs::{
+        change_detection::{DetectChanges, Ref},
         component::Component,
         entity::Entity,
         query::With,
-        system::{Query, Res},
+        resource::Resource,
+        system::{Commands, Query, Res, ResMut},
     },
-    math::{Vec3, primitives::Triangle3d},
-    mesh::{Mesh, Mesh3d},
-    transform::components::GlobalTransform,
-};
-use i_overlay::{
-    core::{fill_rule::FillRule, overlay_rule::OverlayRule},
-    float::single::SingleFloatOverlay,
+    log::warn,
+    math::{
+        Affine3A, Vec3,
+        primitives::{Sphere, Triangle3d},
+    },
+    mesh::{Mesh, Mesh3d, Meshable, VertexAttributeValues},
+    platform::collections::HashMap,
+    reflect::TypePath,
+    tasks::{AsyncComputeTaskPool, Task, block_on, poll_once},
+    transform::{components::Transform, helper::TransformHelper},
 };
+use i_overlay::{core::fill_rule::FillRule, float::simplify::SimplifyShape};
 
-use crate::projectile::{
-    Velocity,
-    util::{air_density, speed_of_sound},
-};
+use crate::projectile::{air_data::AirData, coefficients::AeroCoefficients};
+
+// 642 directions, about 8 deg apart
+const BAKE_SUBDIVISIONS: u32 = 3;
 
 #[derive(Component)]
 #[relationship(relationship_target = Drag)]
@@ -36,196 +40,353 @@ impl Drag {
     }
 }
 
-#[derive(Debug)]
-pub enum AreaCache {
-    None,
-    // Computer{
-    //  angle: f32,
-    //  triangles: Vec<Vec<[f32; 2]>>,
-    // }
-    Final { area: f32 },
+// projected area over a sphere of body frame directions, baked per drag configuration
+#[derive(Asset, TypePath, Debug)]
+pub struct CrossSectionTable {
+    directions: Vec<Vec3>, // unit, body frame
+    areas: Vec<f32>,       // m^2
 }
 
-impl Default for AreaCache {
-    fn default() -> Self {
-        AreaCache::None
+impl CrossSectionTable {
+    pub fn bake(triangles: &[[Vec3; 3]], subdivisions: u32) -> Self {
+        let directions: Vec<Vec3> = icosphere_directions(subdivisions);
+        let areas: Vec<f32> = directions
+            .iter()
+            .map(|direction| projected_area(triangles, *direction))
+            .collect();
+
+        Self { directions, areas }
+    }
+
+    // inverse angle weighted blend of the three nearest baked directions
+    pub fn sample(&self, direction: Vec3) -> f32 {
+        let mut nearest: [(f32, f32); 3] = [(-2.0, 0.); 3]; // (cos angle, area)
+
+        for (baked, area) in self.directions.iter().zip(&self.areas) {
+            let dot: f32 = baked.dot(direction);
+
+            if dot > nearest[2].0 {
+                nearest[2] = (dot, *area);
+                nearest.sort_by(|a, b| b.0.total_cmp(&a.0));
+            }
+        }
+
+        let mut weights: f32 = 0.;
+        let mut area: f32 = 0.;
+
+        for (dot, baked_area) in nearest {
+            let angle: f32 = dot.clamp(-1.0, 1.0).acos();
+            if angle < 1e-4 {
+                return baked_area;
+            }
+
+            weights += 1.0 / angle;
+            area += baked_area / angle;
+        }
+
+        match weights > 0. {
+            true => area / weights,
+            false => 0.,
+        }
+    }
+}
+
+fn icosphere_directions(subdivisions: u32) -> Vec<Vec3> {
+    let Ok(mesh) = Sphere::new(1.0).mesh().ico(subdivisions) else {
+        return vec![Vec3::X];
+    };
+
+    match mesh.attribute(Mesh::ATTRIBUTE_POSITION) {
+        Some(VertexAttributeValues::Float32x3(positions)) => positions
+            .iter()
+            .map(|position| Vec3::from_array(*position).normalize())
+            .collect(),
+        _ => vec![Vec3::X],
     }
 }
 
-#[derive(Component, Default, Debug)]
+fn signed_area(contour: &[[f32; 2]]) -> f32 {
+    let mut area: f32 = 0.;
+    for i in 0..contour.len() {
+        let j = (i + 1) % contour.len();
+        area += contour[i][0] * contour[j][1] - contour[j][0] * contour[i][1];
+    }
+
+    0.5 * area
+}
+
+// area of the union of the triangles seen along direction
+pub fn projected_area(triangles: &[[Vec3; 3]], direction: Vec3) -> f32 {
+    let (u, v) = direction.normalize().any_orthonormal_pair();
+
+    // front and back faces project with opposite winding, make them all counter clockwise
+    // so the non zero fill rule gives the union
+    let contours: Vec<Vec<[f32; 2]>> = triangles
+        .iter()
+        .filter_map(|triangle| {
+            let mut contour: [[f32; 2]; 3] = triangle.map(|vertex| [vertex.dot(u), vertex.dot(v)]);
+
+            let area: f32 = signed_area(&contour);
+            if area.abs() < 1e-9 {
+                return None;
+            }
+            if area < 0. {
+                contour.reverse();
+            }
+
+            Some(contour.to_vec())
+        })
+        .collect();
+
+    if contours.is_empty() {
+        return 0.;
+    }
+
+    // holes wind opposite to their outer contour
+    contours
+        .simplify_shape(FillRule::NonZero)
+        .iter()
+        .map(|shape| {
+            shape
+                .iter()
+                .map(|contour| signed_area(contour))
+                .sum::<f32>()
+                .abs()
+        })
+        .sum()
+}
+
+#[derive(Component, Debug)]
 pub struct CrossSectionArea {
-    cache: AreaCache,
+    pub table: Option<Handle<CrossSectionTable>>,
     pub area: f32,
+    stale: bool, // drag targets changed or their meshes are still loading
 }
 
-pub fn update_cross_section(
-    meshes: Res<Assets<Mesh>>,
+impl Default for CrossSectionArea {
+    fn default() -> Self {
+        Self {
+            table: None,
+            area: 0.,
+            stale: true,
+        }
+    }
+}
 
-    cross_section_components: Query<(&Mesh3d, &GlobalTransform), With<DragTarget>>,
+// drag meshes with their body space placement, rounded to 0.1 mm so identical aircraft match
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CrossSectionKey(Vec<(AssetId<Mesh>, [i32; 12])>);
+
+impl CrossSectionKey {
+    fn new(parts: &[(AssetId<Mesh>, Affine3A)]) -> Self {
+        let mut parts: Vec<(AssetId<Mesh>, [i32; 12])> = parts
+            .iter()
+            .map(|(mesh, to_body)| {
+                (
+                    *mesh,
+                    to_body
+                        .to_cols_array()
+                        .map(|value| (value * 1e4).round() as i32),
+                )
+            })
+            .collect();
+
+        parts.sort();
+        Self(parts)
+    }
+}
+
+// one bake per drag configuration, shared by every aircraft using it
+#[derive(Resource, Default)]
+pub struct CrossSectionTables {
+    baked: HashMap<CrossSectionKey, Handle<CrossSectionTable>>,
+    pending: HashMap<CrossSectionKey, Task<CrossSectionTable>>,
+}
 
-    cross_section_query: Query<(&mut CrossSectionArea, &Drag, &Velocity, &GlobalTransform)>,
+// the previous table stays in use until this configuration is baked
+#[derive(Component)]
+pub struct CrossSectionBake(CrossSectionKey);
+
+pub fn request_cross_section_bake(
+    mut commands: Commands,
+    meshes: Res<Assets<Mesh>>,
+    mut cross_section_tables: ResMut<CrossSectionTables>,
+    transform_helper: TransformHelper,
+
+    drag_targets: Query<&Mesh3d, With<DragTarget>>,
+    mut query: Query<(Entity, &mut CrossSectionArea, Ref<Drag>)>,
 ) {
-    for (mut drag_data, Drag(children), Velocity(velocity), drag_transform) in cross_section_query {
-        if velocity.length().abs() <= 0.0001 {
-            drag_data.cache = AreaCache::None;
-            drag_data.area = 0.;
+    let CrossSectionTables { baked, pending } = &mut *cross_section_tables;
+
+    for (entity, mut cross_section, drag) in &mut query {
+        if drag.is_changed() {
+            cross_section.stale = true;
+        }
+
+        if !cross_section.stale {
             continue;
         }
 
-        let angle = velocity.normalize().dot(*drag_transform.forward());
-
-        let max_angle: f32 = 1. - 5.0_f32.to_radians().cos();
-
-        match (angle >= max_angle, &mut drag_data.cache) {
-            (false, _) | (true, AreaCache::None) => {
-                // compute new cache
-                let (u, v) = {
-                    match velocity.dot(Vec3::X).abs() >= 1. - 0.00001 {
-                        true => {
-                            let u = velocity.cross(Vec3::Y).normalize();
-                            let v = velocity.cross(u).normalize();
-
-                            (u, v)
-                        }
-                        false => {
-                            let u = velocity.cross(Vec3::X).normalize();
-                            let v = velocity.cross(u).normalize();
-
-                            (u, v)
-                        }
-                    }
-                };
-                let mut triangles: Vec<[Vec3; 3]> = vec![];
-                for child in children {
-                    let Ok((mesh_handle, mesh_transform)) = cross_section_components.get(*child)
-                    else {
-                        println!("No mesh handle");
-                        continue;
-                    };
+        // global transforms may not be propagated yet for freshly spawned parts
+        let Ok(body) = transform_helper.compute_global_transform(entity) else {
+            continue;
+        };
+        let to_body = body.affine().inverse();
+
+        let parts: Option<Vec<(AssetId<Mesh>, Affine3A)>> = drag
+            .0
+            .iter()
+            .filter_map(|child| Some((*child, drag_targets.get(*child).ok()?)))
+            .map(|(child, mesh_handle)| {
+                // meshes still loading, try again next frame
+                meshes.get(mesh_handle.id())?;
+                let child_transform = transform_helper.compute_global_transform(child).ok()?;
+
+                Some((mesh_handle.id(), to_body * child_transform.affine()))
+            })
+            .collect();
+
+        let Some(parts) = parts else {
+            continue;
+        };
 
-                    let Some(mesh) = meshes.get(mesh_handle.id()) else {
-                        println!("Mesh not loaded");
-                        continue;
-                    };
+        let key: CrossSectionKey = CrossSectionKey::new(&parts);
+        cross_section.stale = false;
 
-                    let Ok(iter) = mesh.triangles() else {
-                        println!("Failed to get triangles");
-                        continue;
+        if let Some(table) = baked.get(&key) {
+            cross_section.table = Some(table.clone());
+            commands.entity(entity).remove::<CrossSectionBake>();
+            continue;
+        }
+
+        if !pending.contains_key(&key) {
+            let triangles: Vec<[Vec3; 3]> = parts
+                .iter()
+                .filter_map(|(mesh, to_body)| {
+                    let Ok(triangles) = meshes.get(*mesh)?.triangles() else {
+                        warn!("Drag mesh {mesh:?} has no triangles, it is left out of the bake");
+                        return None;
                     };
 
-                    let translation = mesh_transform.translation();
-                    let scale = mesh_transform.scale();
-                    let rotation = mesh_transform.rotation();
-
-                    let global_translation = drag_transform.translation();
-
-                    for Triangle3d { vertices } in iter {
-                        let mut iter = vertices.into_iter().map(|vec| {
-                            rotation * Vec3::new(vec.x * scale.x, vec.y * scale.y, vec.z * scale.z)
-                                + translation
-                                - global_translation
-                        });
-
-                        let vertices: [Vec3; 3] = array::from_fn(|_| iter.next().unwrap());
-
-                        triangles.push(vertices);
-                    }
-                }
-                let triangles: Vec<Vec<[f32; 2]>> = triangles
-                    .into_iter()
-                    .map(|triangles| {
-                        triangles
-                            .into_iter()
-                            .map(|vec: Vec3| {
-                                [
-                                    {
-                                        let vec = vec.project_onto(u);
-                                        let length = vec.length();
-
-                                        match (length * u - vec).length() <= 0.0001 {
-                                            true => length,
-                                            false => -length,
-                                        }
-                                    },
-                                    {
-                                        let vec = vec.project_onto(v);
-                                        let length = vec.length();
-
-                                        match (length * v - vec).length() <= 0.0001 {
-                                            true => length,
-                                            false => -length,
-                                        }
-                                    },
-                                ]
-                            })
-                            .collect::<Vec<[f32; 2]>>()
-                    })
-                    .collect();
-                let mut cross_section: Vec<Vec<Vec<[f32; 2]>>> = vec![];
-                for triangle in triangles {
-                    cross_section =
-                        cross_section.overlay(&triangle, OverlayRule::Union, FillRule::EvenOdd);
-                    break;
-                }
-                let mut total_area = 0.0;
-                for shape in &cross_section {
-                    for contour in shape {
-                        let mut area = 0.0;
-                        for i in 0..contour.len() {
-                            let j = (i + 1) % contour.len();
-                            area += contour[i][0] * contour[j][1] - contour[j][0] * contour[i][1];
-                        }
-                        area *= 0.5;
-                        total_area += area;
-                    }
-                }
-                total_area *= 148.6884931;
-                // println!("{:?}", cross_section);
-
-                drag_data.cache = AreaCache::Final { area: total_area };
-
-                drag_data.area = total_area;
-            }
-            (true, AreaCache::Final { area }) => {
-                // no new cache
-                drag_data.area = *area;
-            }
+                    Some(triangles.map(|Triangle3d { vertices }| {
+                        vertices.map(|vertex| to_body.transform_point3(vertex))
+                    }))
+                })
+                .flatten()
+                .collect();
+
+            let task = AsyncComputeTaskPool::get()
+                .spawn(async move { CrossSectionTable::bake(&triangles, BAKE_SUBDIVISIONS) });
+
+            pending.insert(key.clone(), task);
+        }
+
+        // replaces the wait for an older configuration, its bake still lands in the cache
+        commands.entity(entity).insert(CrossSectionBake(key));
+    }
+}
+
+pub fn poll_cross_section_bake(
+    mut commands: Commands,
+    mut tables: ResMut<Assets<CrossSectionTable>>,
+    mut cross_section_tables: ResMut<CrossSectionTables>,
+
+    mut query: Query<(Entity, &mut CrossSectionArea, &CrossSectionBake)>,
+) {
+    let CrossSectionTables { baked, pending } = &mut *cross_section_tables;
+
+    let finished: Vec<(CrossSectionKey, CrossSectionTable)> = pending
+        .iter_mut()
+        .filter_map(|(key, task)| block_on(poll_once(task)).map(|table| (key.clone(), table)))
+        .collect();
+
+    for (key, table) in finished {
+        pending.remove(&key);
+        baked.insert(key, tables.add(table));
+    }
+
+    for (entity, mut cross_section, bake) in &mut query {
+        let Some(table) = baked.get(&bake.0) else {
+            continue;
+        };
+
+        cross_section.table = Some(table.clone());
+        commands.entity(entity).remove::<CrossSectionBake>();
+    }
+}
+
+pub fn update_cross_section(
+    tables: Res<Assets<CrossSectionTable>>,
+
+    query: Query<(&mut CrossSectionArea, &AirData, &Transform)>,
+) {
+    for (mut cross_section, air_data, transform) in query {
+        let Some(table) = cross_section
+            .table
+            .as_ref()
+            .and_then(|table| tables.get(table))
+        else {
+            continue;
         };
-        // println!("{:?}", drag_data.area);
+
+        let speed: f32 = air_data.true_airspeed;
+        if speed < 1e-3 {
+            continue;
+        }
+
+        let direction: Vec3 = transform.rotation.inverse() * air_data.airspeed / speed;
+
+        cross_section.area = table.sample(direction);
     }
 }
 
-const C_D_SUBSONIC: f32 = 0.02;
-const C_D_TRANSONIC_SPIKE: f32 = 0.10;
-const C_D_SUPERSONIC_BASE: f32 = 0.04;
 pub fn drag_force(
+    coefficients: &AeroCoefficients,
+    air_data: &AirData,
     cross_section_area: f32,
-    velocity: &Velocity,
-    temperature: f32,
-    air_pressure: f32,
 ) -> Vec3 {
-    let air_density: f32 = air_density(air_pressure, temperature);
-
-    let speed = velocity.0.length();
+    let speed: f32 = air_data.true_airspeed;
     if speed < 1e-3 {
         return Vec3::ZERO;
     }
-    let velocity_dir = velocity.0.normalize();
-
-    let speed_of_sound = speed_of_sound(temperature);
-    let mach_number = speed / speed_of_sound;
-
-    let drag_coefficient = if mach_number < 0.8 {
-        C_D_SUBSONIC
-    } else if mach_number < 1.2 {
-        let transition_factor = (mach_number - 0.8) / 0.4;
-        C_D_SUBSONIC + transition_factor * (C_D_TRANSONIC_SPIKE - C_D_SUBSONIC)
-    } else {
-        C_D_SUPERSONIC_BASE + 0.02 * (mach_number - 1.2)
-    };
+    let velocity_dir: Vec3 = air_data.airspeed / speed;
+
+    let drag_coefficient: f32 = coefficients.cd(air_data.alpha, air_data.beta, air_data.mach);
 
-    let dynamic_pressure = 0.5 * air_density * speed * speed;
-    let drag_magnitude = dynamic_pressure * drag_coefficient * cross_section_area;
+    let drag_magnitude: f32 = air_data.dynamic_pressure * drag_coefficient * cross_section_area;
 
     -drag_magnitude * velocity_dir
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy::asset::uuid::Uuid;
+
+    use super::*;
+
+    #[test]
+    fn cross_section_key_ignores_order_and_rounding_noise() {
+        let fuselage: AssetId<Mesh> = AssetId::Uuid {
+            uuid: Uuid::from_u128(1),
+        };
+        let tank: AssetId<Mesh> = AssetId::Uuid {
+            uuid: Uuid::from_u128(2),
+        };
+
+        let tip: Affine3A = Affine3A::from_translation(Vec3::new(1.0, -0.3, 3.4));
+        let noisy_tip: Affine3A = Affine3A::from_translation(Vec3::new(1.000_001, -0.3, 3.4));
+
+        let key = CrossSectionKey::new(&[(fuselage, Affine3A::IDENTITY), (tank, tip)]);
+
+        assert_eq!(
+            key,
+            CrossSectionKey::new(&[(tank, noisy_tip), (fuselage, Affine3A::IDENTITY)])
+        );
+
+        // same meshes, different placement
+        assert_ne!(
+            key,
+            CrossSectionKey::new(&[(fuselage, tip), (tank, Affine3A::IDENTITY)])
+        );
+    }
+}
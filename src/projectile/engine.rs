@@ -1,32 +1,227 @@
 use std::f32::consts::PI;
 
 use bevy::{
-    ecs::component::Component,
+    ecs::{
+        component::Component,
+        system::{Query, Res, Single},
+    },
     math::{Quat, Vec3},
+    time::Time,
     transform::components::Transform,
 };
 
+use crate::{
+    cf104::console::throttle::Throttle,
+    projectile::{air_data::AirData, util::celsius_to_kelvin},
+};
+
+const SEA_LEVEL_PRESSURE: f32 = 101_325.0; // Pa
+const SEA_LEVEL_TEMPERATURE: f32 = 288.15; // K
+
 #[derive(Component, Debug)]
 pub struct Engine {
-    pub max_thrust: f32,
-    pub ramp_time: f32,
-    pub elapsed: f32,
+    pub max_thrust: f32,         // N, military (dry) sea level static
+    pub afterburner_thrust: f32, // N, max afterburner sea level static
     pub direction: Quat,
+
+    // throttle travel (fraction)
+    pub cutoff: f32,
+    pub afterburner_detent: f32,
+
+    // spool
+    pub idle_rpm: f32,         // fraction of max rpm
+    pub start_time: f32,       // s, light off to idle
+    pub spool_up_time: f32,    // s, time constant above idle
+    pub spool_down_time: f32,  // s, time constant
+    pub afterburner_time: f32, // s, light to full afterburner
+
+    // specific fuel consumption (kg/N/s)
+    pub dry_tsfc: f32,
+    pub afterburner_tsfc: f32,
+    pub idle_fuel_flow: f32, // kg/s
+
+    // fuel feed
+    pub negative_g_limit: f32, // s, before the feed tank unports
+    pub max_alpha: f32,        // rad, inlet distortion
+    pub high_alpha_limit: f32, // s
+
+    // state
+    pub lit: bool,
+    pub flamed_out: bool,
+    pub fuel_starved: bool,
+    pub negative_g: f32,  // s
+    pub high_alpha: f32,  // s
+    pub rpm: f32,         // fraction of max rpm
+    pub afterburner: f32, // 0..1
+    pub egt: f32,         // C
+    pub fuel_flow: f32,   // kg/s
     pub current_thrust: f32,
 }
 
 impl Engine {
+    // J79-GE-11A
     pub fn cf104() -> Self {
         Self {
             max_thrust: 44_000.0,
-            ramp_time: 5.0,
-            elapsed: 0.0,
+            afterburner_thrust: 70_300.0,
             direction: Quat::from_rotation_y(PI),
+
+            cutoff: 0.05,
+            afterburner_detent: 0.85,
+
+            idle_rpm: 0.67,
+            start_time: 17.0,
+            spool_up_time: 1.6,
+            spool_down_time: 1.2,
+            afterburner_time: 1.0,
+
+            dry_tsfc: 2.38e-5,
+            afterburner_tsfc: 5.58e-5,
+            idle_fuel_flow: 0.12,
+
+            negative_g_limit: 8.0,
+            max_alpha: 30f32.to_radians(),
+            high_alpha_limit: 2.0,
+
+            lit: false,
+            flamed_out: false,
+            fuel_starved: false,
+            negative_g: 0.,
+            high_alpha: 0.,
+            rpm: 0.,
+            afterburner: 0.,
+            egt: 15.,
+            fuel_flow: 0.,
             current_thrust: 0.0,
         }
     }
+
     pub fn thrust_vector(&self, transform: &Transform) -> Vec3 {
         let world_dir = transform.rotation * self.direction * Vec3::X;
         world_dir * self.current_thrust
     }
+
+    // throttle (0..1) -> (dry demand, afterburner demand)
+    pub fn demand(&self, throttle: f32) -> (f32, f32) {
+        let dry: f32 =
+            ((throttle - self.cutoff) / (self.afterburner_detent - self.cutoff)).clamp(0.0, 1.0);
+        let afterburner: f32 = ((throttle - self.afterburner_detent)
+            / (1.0 - self.afterburner_detent))
+            .clamp(0.0, 1.0);
+
+        (dry, afterburner)
+    }
+
+    // fraction of the idle to military rpm band
+    #[inline]
+    pub fn core_power(&self) -> f32 {
+        ((self.rpm - self.idle_rpm) / (1.0 - self.idle_rpm)).clamp(0.0, 1.0)
+    }
+
+    fn spool(&mut self, target: f32, dt: f32) {
+        if target > self.rpm && self.rpm < self.idle_rpm && self.lit {
+            // starter / light off
+            self.rpm = (self.rpm + self.idle_rpm / self.start_time * dt).min(target);
+            return;
+        }
+
+        let time_constant: f32 = match target > self.rpm {
+            true => self.spool_up_time,
+            false => match self.lit {
+                true => self.spool_down_time,
+                false => self.spool_down_time * 4.0,
+            },
+        };
+
+        self.rpm += (target - self.rpm) * (1.0 - (-dt / time_constant).exp());
+    }
+}
+
+pub fn update_engine_thrust(
+    time: Res<Time>,
+    throttle: Single<&Throttle>,
+    mut engine_query: Query<(&mut Engine, &AirData)>,
+) {
+    let dt: f32 = time.delta_secs();
+    let throttle: f32 = (throttle.0 / 100.).clamp(0.0, 1.0);
+
+    for (mut engine, air_data) in &mut engine_query {
+        let (dry_demand, afterburner_demand) = engine.demand(throttle);
+
+        // --- Light off / flame out ---
+        let fuel_on: bool = throttle > engine.cutoff;
+
+        if !fuel_on {
+            engine.flamed_out = false;
+        } else if engine.lit && engine.fuel_starved {
+            engine.flamed_out = true;
+        } else if engine.flamed_out && !engine.fuel_starved && dry_demand < 0.1 {
+            // relight with the throttle back near idle
+            engine.flamed_out = false;
+        }
+
+        engine.lit = fuel_on && !engine.flamed_out && !engine.fuel_starved;
+
+        // --- Spool ---
+        let target_rpm: f32 = match engine.lit {
+            true => engine.idle_rpm + (1.0 - engine.idle_rpm) * dry_demand,
+            false => (0.15 * air_data.mach).min(0.2), // windmilling
+        };
+        engine.spool(target_rpm, dt);
+
+        // afterburner only lights at military rpm
+        let afterburner_target: f32 = match engine.lit && engine.rpm > 0.97 {
+            true => afterburner_demand,
+            false => 0.,
+        };
+        let afterburner_delta: f32 = dt / engine.afterburner_time;
+        engine.afterburner +=
+            (afterburner_target - engine.afterburner).clamp(-afterburner_delta, afterburner_delta);
+
+        // --- Thrust lapse ---
+        let delta: f32 = air_data.pressure / SEA_LEVEL_PRESSURE;
+        let theta: f32 = air_data.temperature / SEA_LEVEL_TEMPERATURE;
+        let mach: f32 = air_data.mach.min(2.0);
+
+        let dry_lapse: f32 = delta / theta.sqrt() * (1.0 + 0.25 * mach * mach);
+        let afterburner_lapse: f32 = delta / theta.sqrt() * (1.0 + 0.5 * mach * mach);
+
+        // --- Thrust ---
+        let core_thrust: f32 = match engine.lit {
+            true if engine.rpm < engine.idle_rpm => 0.05 * (engine.rpm / engine.idle_rpm).powi(2),
+            true => 0.05 + 0.95 * engine.core_power().powi(2),
+            false => 0.,
+        };
+
+        let dry_thrust: f32 = engine.max_thrust * core_thrust * dry_lapse;
+        let afterburner_thrust: f32 = (engine.afterburner_thrust - engine.max_thrust)
+            * engine.afterburner
+            * afterburner_lapse;
+
+        engine.current_thrust = dry_thrust + afterburner_thrust;
+
+        // --- Fuel flow ---
+        let tsfc_scale: f32 = (1.0 + 0.35 * mach) * theta.sqrt();
+
+        engine.fuel_flow = match engine.lit {
+            true => (tsfc_scale
+                * (engine.dry_tsfc * dry_thrust + engine.afterburner_tsfc * afterburner_thrust))
+                .max(engine.idle_fuel_flow * (engine.rpm / engine.idle_rpm).min(1.0)),
+            false => 0.,
+        };
+
+        // --- Exhaust gas temperature ---
+        let ambient: f32 = air_data.temperature - celsius_to_kelvin(0.);
+        let egt_target: f32 = match engine.lit {
+            true => {
+                ambient
+                    + 330.0 * (engine.rpm / engine.idle_rpm).min(1.0)
+                    + 310.0 * engine.core_power().powi(2)
+                    + 20.0 * engine.afterburner
+            }
+            false => ambient,
+        };
+
+        engine.egt += (egt_target - engine.egt) * (1.0 - (-dt / 2.0).exp());
+    }
 }
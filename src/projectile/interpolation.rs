@@ -0,0 +1,123 @@
+use bevy::{
+    ecs::{
+        component::Component,
+        schedule::SystemSet,
+        system::{Query, Res},
+    },
+    math::{Quat, Vec3},
+    time::{Fixed, Time},
+    transform::components::Transform,
+};
+
+use crate::{projectile::Velocity, world::GlobalPosition};
+
+#[derive(Debug, Clone, Copy)]
+pub struct PhysicsState {
+    pub position: GlobalPosition,
+    pub translation: Vec3,
+    pub rotation: Quat,
+    pub velocity: Vec3,
+}
+
+impl Default for PhysicsState {
+    fn default() -> Self {
+        Self {
+            position: GlobalPosition::zero(),
+            translation: Vec3::ZERO,
+            rotation: Quat::IDENTITY,
+            velocity: Vec3::ZERO,
+        }
+    }
+}
+
+impl PhysicsState {
+    pub fn lerp(&self, other: &Self, t: f32) -> Self {
+        let lerp_f64 = |a: f64, b: f64| a + (b - a) * t as f64;
+
+        Self {
+            position: GlobalPosition {
+                x: lerp_f64(self.position.x, other.position.x),
+                y: lerp_f64(self.position.y, other.position.y),
+                z: lerp_f64(self.position.z, other.position.z),
+            },
+            translation: self.translation.lerp(other.translation, t),
+            rotation: self.rotation.slerp(other.rotation, t),
+            velocity: self.velocity.lerp(other.velocity, t),
+        }
+    }
+}
+
+// last two fixed step states, the transform shows a blend of them between ticks
+#[derive(Component, Debug, Default)]
+pub struct Interpolated {
+    previous: PhysicsState,
+    current: PhysicsState,
+    render: PhysicsState,
+    initialized: bool,
+}
+
+impl Interpolated {
+    // blended state for this frame, read by the instruments instead of the raw state
+    pub fn render(&self) -> Option<&PhysicsState> {
+        self.initialized.then_some(&self.render)
+    }
+}
+
+// PostUpdate, ahead of the transform propagation
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct InterpolationSet;
+
+// FixedFirst, physics must see its own state and not the blended one
+pub fn restore_physics_state(mut query: Query<(&mut Transform, &Interpolated)>) {
+    for (mut transform, interpolated) in &mut query {
+        if !interpolated.initialized {
+            continue;
+        }
+
+        transform.translation = interpolated.current.translation;
+        transform.rotation = interpolated.current.rotation;
+    }
+}
+
+// FixedLast
+pub fn store_physics_state(
+    mut query: Query<(&mut Interpolated, &Transform, &GlobalPosition, &Velocity)>,
+) {
+    for (mut interpolated, transform, position, velocity) in &mut query {
+        let state = PhysicsState {
+            position: *position,
+            translation: transform.translation,
+            rotation: transform.rotation,
+            velocity: velocity.0,
+        };
+
+        if !interpolated.initialized {
+            interpolated.previous = state;
+            interpolated.render = state;
+            interpolated.initialized = true;
+        } else {
+            interpolated.previous = interpolated.current;
+        }
+        interpolated.current = state;
+    }
+}
+
+pub fn interpolate_transforms(
+    time: Res<Time<Fixed>>,
+    mut query: Query<(&mut Transform, &mut Interpolated)>,
+) {
+    let t: f32 = time.overstep_fraction().clamp(0.0, 1.0);
+
+    for (mut transform, mut interpolated) in &mut query {
+        if !interpolated.initialized {
+            continue;
+        }
+
+        let render: PhysicsState = interpolated.previous.lerp(&interpolated.current, t);
+
+        transform.translation = render.translation;
+        transform.rotation = render.rotation;
+
+        interpolated.render = render;
+    }
+}
@@ -0,0 +1,334 @@
+use bevy::{
+    ecs::{
+        component::Component,
+        entity::Entity,
+        message::MessageWriter,
+        query::{Has, With, Without},
+        system::{Commands, Query, Res, ResMut},
+    },
+    log::{info, warn},
+    math::{Quat, Vec3},
+    time::Time,
+    transform::components::Transform,
+};
+
+use crate::{
+    player::Player,
+    projectile::{
+        AngularVelocity, Crashed, Grounded, Projectile, SteeringWheel, Velocity,
+        air_data::AirData,
+        brakes::{BrakeSide, Brakes},
+        collision::{HullContact, ImpactKind, ImpactMessage},
+        mass::CenterOfGravity,
+        tire::{RunwayCondition, Tire},
+        util::BODY_FORWARD,
+    },
+    world::{GlobalPosition, MovingOrigin, ground::Terrain},
+};
+
+pub const NOSE: usize = 0;
+pub const LEFT: usize = 1;
+pub const RIGHT: usize = 2;
+
+#[derive(Debug)]
+pub struct Strut {
+    pub position: Vec3, // body space wheel contact at full extension
+    pub travel: f32,    // m
+    pub stiffness: f32, // N/m
+    pub damping: f32,   // N s/m
+    pub tire: Tire,
+    pub steerable: bool,
+    pub brake: Option<BrakeSide>,
+
+    pub compression: f32, // m
+    pub load: f32,        // N
+    pub brake_power: f32, // W
+}
+
+impl Strut {
+    fn cf_104(position: Vec3, steerable: bool, brake: Option<BrakeSide>) -> Self {
+        Self {
+            position,
+            travel: 0.3,
+            stiffness: 235_000.0,
+            damping: 29_000.0,
+            tire: Tire::cf_104(),
+            steerable,
+            brake,
+
+            compression: 0.,
+            load: 0.,
+            brake_power: 0.,
+        }
+    }
+
+    #[inline]
+    pub fn in_contact(&self) -> bool {
+        self.load > 0.
+    }
+}
+
+#[derive(Component, Debug)]
+pub struct LandingGear {
+    pub struts: [Strut; 3], // nose, left, right
+
+    pub transit_time: f32,   // s, up to down
+    pub retract_height: f32, // m, wheel travel into the wells
+    pub limit_speed: f32,    // m/s equivalent airspeed, gear down or in transit
+    pub drag_area: f32,      // m^2, fully extended
+
+    pub lever_down: bool,
+    pub extension: f32, // 0 (up) .. 1 (down)
+    pub failed: bool,
+}
+
+impl LandingGear {
+    pub fn cf_104() -> Self {
+        Self {
+            transit_time: 7.0,
+            retract_height: 0.7,
+            limit_speed: 134.0, // 260 kt
+            drag_area: 0.9,
+
+            lever_down: true,
+            extension: 1.0,
+            failed: false,
+
+            struts: [
+                // body axes in util.rs, the nose is -x and the left wing +z
+                Strut::cf_104(Vec3::new(-3.7, -1.6, 0.), true, None),
+                Strut::cf_104(Vec3::new(0.7, -1.6, 1.37), false, Some(BrakeSide::Left)),
+                Strut::cf_104(Vec3::new(0.7, -1.6, -1.37), false, Some(BrakeSide::Right)),
+            ],
+        }
+    }
+
+    pub fn total_load(&self) -> f32 {
+        self.struts.iter().map(|strut| strut.load).sum()
+    }
+
+    #[inline]
+    pub fn down_and_locked(&self) -> bool {
+        self.extension >= 1.0 && !self.failed
+    }
+
+    pub fn drag_force(&self, air_data: &AirData) -> Vec3 {
+        let speed: f32 = air_data.true_airspeed;
+        if speed < 1e-3 {
+            return Vec3::ZERO;
+        }
+
+        -air_data.airspeed / speed * air_data.dynamic_pressure * self.drag_area * self.extension
+    }
+}
+
+#[derive(Component, Default, Debug)]
+pub struct GearLoads {
+    pub force: Vec3,  // world space
+    pub moment: Vec3, // body space, about the cg
+}
+
+pub fn update_gear_extension(
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut LandingGear, &AirData), (With<Projectile>, Without<Crashed>)>,
+) {
+    let dt: f32 = time.delta_secs();
+
+    for (entity, mut gear, air_data) in &mut query {
+        if gear.failed {
+            continue;
+        }
+
+        // --- Overspeed ---
+        let equivalent_airspeed: f32 = (2.0 * air_data.dynamic_pressure / 1.225).sqrt();
+
+        if gear.extension > 0. && equivalent_airspeed > gear.limit_speed {
+            gear.failed = true;
+            warn!("Gear overspeed on {entity:?}, the landing gear has failed");
+            continue;
+        }
+
+        // --- Retract / extend ---
+        // squat switch, the gear stays down while the struts carry load
+        let target: f32 = match gear.lever_down || gear.total_load() > 0. {
+            true => 1.0,
+            false => 0.0,
+        };
+
+        let max_delta: f32 = dt / gear.transit_time;
+        gear.extension += (target - gear.extension).clamp(-max_delta, max_delta);
+    }
+}
+
+pub fn update_landing_gear(
+    terrain: Terrain,
+    runway_condition: Res<RunwayCondition>,
+    mut moving_center: ResMut<MovingOrigin>,
+
+    mut commands: Commands,
+    mut impact_writer: MessageWriter<ImpactMessage>,
+    mut query: Query<
+        (
+            Entity,
+            &mut LandingGear,
+            &mut GearLoads,
+            &Transform,
+            &GlobalPosition,
+            &Velocity,
+            &AngularVelocity,
+            &CenterOfGravity,
+            &AirData,
+            Option<&Brakes>,
+            Option<&SteeringWheel>,
+            Has<Grounded>,
+            Has<HullContact>,
+            Has<Player>,
+        ),
+        (With<Projectile>, Without<Crashed>),
+    >,
+) {
+    for (
+        entity,
+        mut gear,
+        mut gear_loads,
+        transform,
+        position,
+        velocity,
+        angular_velocity,
+        center_of_gravity,
+        air_data,
+        brakes,
+        steering_wheel,
+        grounded,
+        on_hull,
+        player,
+    ) in &mut query
+    {
+        let rotation = transform.rotation;
+
+        let steering_angle: f32 = steering_wheel.map_or(0., |wheel| wheel.current_angle);
+
+        let locked: bool = gear.down_and_locked();
+        let retraction: Vec3 = Vec3::Y * (1.0 - gear.extension) * gear.retract_height;
+
+        // --- Gear drag ---
+        // acts at the centroid of the struts
+        let drag: Vec3 = gear.drag_force(air_data);
+        let drag_arm: Vec3 = gear
+            .struts
+            .iter()
+            .map(|strut| strut.position + retraction)
+            .sum::<Vec3>()
+            / 3.0
+            - center_of_gravity.0;
+
+        let mut force: Vec3 = drag;
+        let mut moment: Vec3 = drag_arm.cross(rotation.inverse() * drag);
+
+        for strut in &mut gear.struts {
+            let wheel: Vec3 = strut.position + retraction;
+            let offset: Vec3 = rotation * wheel;
+
+            let wheel_x: f64 = position.x + offset.x as f64;
+            let wheel_y: f64 = position.y + offset.y as f64;
+            let wheel_z: f64 = position.z + offset.z as f64;
+
+            // field level until the height data is loaded
+            let ground: f32 = terrain.height(wheel_x, wheel_z).unwrap_or(0.);
+            let penetration: f32 = (ground as f64 - wheel_y) as f32;
+
+            if penetration <= 0. {
+                strut.compression = 0.;
+                strut.load = 0.;
+                strut.brake_power = 0.;
+                continue;
+            }
+
+            // only a locked gear carries load, otherwise the hull meets the terrain
+            if !locked {
+                strut.compression = 0.;
+                strut.load = 0.;
+                strut.brake_power = 0.;
+                continue;
+            }
+
+            // --- Spring damper ---
+            let wheel_velocity: Vec3 = velocity.0 + rotation * angular_velocity.0.cross(wheel);
+            let compression_rate: f32 = -wheel_velocity.y;
+
+            strut.compression = penetration.min(strut.travel);
+            let bottomed: f32 = (penetration - strut.travel).max(0.);
+
+            strut.load = (strut.stiffness * strut.compression
+                + strut.damping * compression_rate
+                + 10.0 * strut.stiffness * bottomed)
+                .max(0.);
+
+            // --- Tire ---
+            let steering: Quat = match strut.steerable {
+                true => Quat::from_rotation_y(steering_angle),
+                false => Quat::IDENTITY,
+            };
+            let heading: Vec3 = rotation * steering * BODY_FORWARD;
+            let heading: Vec3 = Vec3::new(heading.x, 0., heading.z).normalize_or(BODY_FORWARD);
+
+            let brake_demand: f32 = match (strut.brake, brakes) {
+                (Some(side), Some(brakes)) => brakes.demand(side),
+                _ => 0.,
+            };
+
+            let (tire_force, brake_power) = strut.tire.force(
+                heading,
+                wheel_velocity,
+                strut.load,
+                brake_demand,
+                *runway_condition,
+            );
+            strut.brake_power = brake_power;
+
+            let strut_force: Vec3 = Vec3::Y * strut.load + tire_force;
+            let arm: Vec3 = wheel - center_of_gravity.0;
+
+            force += strut_force;
+            moment += arm.cross(rotation.inverse() * strut_force);
+        }
+
+        gear_loads.force = force;
+        gear_loads.moment = moment;
+
+        // --- Touchdown / liftoff ---
+        let contact: bool = gear.struts.iter().any(Strut::in_contact);
+
+        match (contact, grounded) {
+            (true, false) => {
+                let kind: ImpactKind = ImpactKind::classify(velocity.0, rotation);
+
+                // beyond the design sink rate the gear collapses
+                match kind {
+                    ImpactKind::Crash => {
+                        gear.failed = true;
+                        commands.entity(entity).insert(Crashed);
+                    }
+                    _ => {
+                        commands.entity(entity).insert(Grounded);
+                    }
+                }
+
+                impact_writer.write(ImpactMessage::new(
+                    entity, kind, velocity.0, rotation, position,
+                ));
+            }
+            // still on its hull, the terrain collision keeps it grounded
+            (false, true) if !on_hull => {
+                // the world origin follows the player's aircraft once airborne
+                if player {
+                    moving_center.0 = Some(entity);
+                }
+
+                commands.entity(entity).remove::<Grounded>();
+                info!("Takeoff, {entity:?} is airborne");
+            }
+            _ => {}
+        }
+    }
+}
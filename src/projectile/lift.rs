@@ -1,44 +1,54 @@
-use bevy::math::Vec3;
-
-const CF104_WING_AREA: f32 = 18.22; // m^2
-pub const CF104_CL0: f32 = 0.8;
-pub const CF104_CL_ALPHA: f32 = 5.7;
-pub const CF104_STALL_ALPHA: f32 = 15.0_f32.to_radians();
-pub const CF104_INCIDENT_OFFSET: f32 = -2.0_f32.to_radians();
-
-#[inline]
-fn angle_of_attack(forward: &Vec3, velocity: &Vec3, up: &Vec3) -> f32 {
-    let rel_air = -velocity;
-    let vel_proj = rel_air - rel_air.dot(*up) * up;
-    let angle = forward.angle_between(vel_proj);
-    let sign = forward.cross(vel_proj).dot(*up).signum();
-
-    angle * sign + CF104_INCIDENT_OFFSET
-}
-
-#[inline]
-fn cl(alpha: f32) -> f32 {
-    if alpha.abs() < CF104_STALL_ALPHA {
-        CF104_CL0 + CF104_CL_ALPHA * alpha
-    } else {
-        CF104_CL0 + CF104_CL_ALPHA * CF104_STALL_ALPHA * (alpha / CF104_STALL_ALPHA).cos()
+use bevy::math::{Quat, Vec3};
+
+use crate::projectile::{
+    air_data::AirData, coefficients::AeroCoefficients, control_surfaces::ControlSurfaces,
+    util::BODY_RIGHT,
+};
+
+pub fn lift_force(
+    coefficients: &AeroCoefficients,
+    air_data: &AirData,
+    rotation: Quat,
+    wing_area: f32,
+) -> Vec3 {
+    let speed: f32 = air_data.true_airspeed;
+
+    if speed < 1e-3 {
+        return Vec3::ZERO;
     }
-}
 
-pub fn lift_force(forward: &Vec3, velocity: &Vec3, up: &Vec3, rho: f32) -> Vec3 {
-    let vel_proj: Vec3 = velocity.project_onto(*forward);
+    let right: Vec3 = rotation * BODY_RIGHT;
+
+    // perpendicular to the relative wind, in the plane of symmetry
+    let lift_dir: Vec3 = right.cross(air_data.airspeed).normalize_or_zero();
 
-    let v_mag: f32 = vel_proj.length();
+    let cl: f32 = coefficients.cl(air_data.alpha, air_data.beta, air_data.mach);
+    let lift_mag: f32 = air_data.dynamic_pressure * wing_area * cl;
 
-    if v_mag < 1e-3 {
+    // println!("v_mag:{speed:?}\tAoA:{:?}\tcl{cl:?}\tlift:{lift_mag:?}", air_data.alpha);
+
+    lift_dir * lift_mag
+}
+
+pub fn side_force(
+    coefficients: &AeroCoefficients,
+    surfaces: &ControlSurfaces,
+    air_data: &AirData,
+    rotation: Quat,
+    wing_area: f32,
+) -> Vec3 {
+    if air_data.true_airspeed < 1e-3 {
         return Vec3::ZERO;
     }
 
-    let alpha = angle_of_attack(forward, velocity, up);
-    let cl = cl(alpha);
-    let lift_mag = 0.5 * rho * v_mag * v_mag * CF104_WING_AREA * cl;
+    let right: Vec3 = rotation * BODY_RIGHT;
+
+    let rudder_effectiveness: f32 =
+        coefficients.rudder_effectiveness(air_data.alpha, air_data.mach);
 
-    // println!("v_mag:{v_mag:?}\tAoA:{alpha:?}\tcl{cl:?}\tlift:{lift_mag:?}");
+    let cy: f32 = coefficients.cy(air_data.alpha, air_data.beta, air_data.mach)
+        + coefficients.derivatives.cy_dr * surfaces.rudder * rudder_effectiveness;
+    let side_mag: f32 = air_data.dynamic_pressure * wing_area * cy;
 
-    up.normalize() * lift_mag
+    right * side_mag
 }
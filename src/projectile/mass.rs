@@ -3,13 +3,24 @@ use bevy::{
         bundle::Bundle,
         component::Component,
         entity::Entity,
+        hierarchy::ChildOf,
         query::{With, Without},
-        system::{Query, Res, Single},
+        system::{Commands, Query, Res},
     },
+    math::{Mat3, Vec3},
     time::Time,
+    transform::components::{GlobalTransform, Transform},
 };
 
-use crate::cf104::console::throttle::Throttle;
+use crate::{
+    projectile::{
+        FreeFallBundle, GForceCache, Velocity, air_data::AirData, drag::DragTarget, engine::Engine,
+        structure::Structure,
+    },
+    world::GlobalPosition,
+};
+
+const TIP_TANK_DRY_MASS: f32 = 90.0; // kg, only carried once released
 
 #[derive(Component, Default)]
 #[relationship_target(relationship = MassComponent, linked_spawn)]
@@ -19,32 +30,53 @@ pub struct Mass(Vec<Entity>);
 #[relationship(relationship_target = Mass)]
 pub struct MassComponent(pub Entity);
 
+// kg, position in body space (util.rs: -x -> forward, y -> up, -z -> right)
 #[derive(Component)]
-pub struct MassData(f32);
+pub struct MassData(f32, Vec3);
 
 impl MassData {
-    pub fn new(weight: f32) -> Self {
-        MassData(weight)
+    pub fn new(weight: f32, position: Vec3) -> Self {
+        MassData(weight, position)
+    }
+}
+
+// body axes: x -> roll, y -> yaw, z -> pitch (kg m^2)
+#[derive(Component, Debug)]
+pub struct Inertia(pub Mat3);
+
+impl Inertia {
+    pub fn cf_104() -> Self {
+        Self(Mat3::from_diagonal(Vec3::new(4_812.0, 80_902.0, 79_467.0)))
     }
 }
 
+// inertia of a mass component about its own centroid (body axes)
+#[derive(Component, Debug)]
+pub struct MassInertia(pub Mat3);
+
+#[derive(Component, Default, Debug)]
+pub struct CenterOfGravity(pub Vec3); // body space
+
 #[derive(Bundle)]
 pub struct MassBundle {
     mass_component: MassComponent,
     mass_data: MassData,
+    mass_inertia: MassInertia,
 }
 
 impl MassBundle {
     pub fn empty_cf_104(parent_mass: Entity) -> Self {
         Self {
             mass_component: MassComponent(parent_mass),
-            mass_data: MassData::new(6_300.),
+            mass_data: MassData::new(6_300., Vec3::new(-0.05, 0., 0.)),
+            mass_inertia: MassInertia(Mat3::from_diagonal(Vec3::new(4_800.0, 80_000.0, 78_500.0))),
         }
     }
     pub fn nuke(parent_mass: Entity) -> Self {
         Self {
             mass_component: MassComponent(parent_mass),
-            mass_data: MassData::new(226.7962),
+            mass_data: MassData::new(226.7962, Vec3::new(-0.3, -0.7, 0.)),
+            mass_inertia: MassInertia(Mat3::ZERO),
         }
     }
 }
@@ -52,6 +84,14 @@ impl MassBundle {
 #[derive(Component)]
 pub struct ExternalTank;
 
+// cockpit fuel panel state
+#[derive(Component, Debug, Default)]
+pub struct FuelSystem {
+    pub transfer: bool,
+    pub pressurized: bool,
+    pub jettison: bool,
+}
+
 #[derive(Component)]
 pub struct Tank {
     max_capacity: f32,
@@ -70,10 +110,10 @@ pub struct InternalFuelTankBundle {
 }
 
 impl InternalFuelTankBundle {
-    pub fn new(max_capacity: f32, mass: Entity) -> Self {
+    pub fn new(max_capacity: f32, position: Vec3, mass: Entity) -> Self {
         Self {
             mass_component: MassComponent(mass),
-            mass_data: MassData::new(max_capacity),
+            mass_data: MassData::new(max_capacity, position),
             tank: Tank {
                 max_capacity: max_capacity,
                 active: false,
@@ -94,10 +134,10 @@ pub struct ExternalFuelTankBundle {
 }
 
 impl ExternalFuelTankBundle {
-    pub fn new(max_capacity: f32, mass: Entity, internal_tank: Entity) -> Self {
+    pub fn new(max_capacity: f32, position: Vec3, mass: Entity, internal_tank: Entity) -> Self {
         Self {
             mass_component: MassComponent(mass),
-            mass_data: MassData::new(max_capacity),
+            mass_data: MassData::new(max_capacity, position),
             tank: Tank {
                 max_capacity: max_capacity,
                 active: false,
@@ -151,28 +191,110 @@ pub fn update_fuel_mass_system(
 }
 
 pub fn update_tank_flow_rate(
-    throttle: Single<&Throttle>,
-    mut internal_tanks: Query<&mut Tank, Without<ExternalTank>>,
-    mut external_tanks: Query<&mut Tank, With<ExternalTank>>,
+    engines: Query<&Engine>,
+    fuel_systems: Query<&FuelSystem>,
+    mut internal_tanks: Query<(&mut Tank, &MassComponent), Without<ExternalTank>>,
+    mut external_tanks: Query<(&mut Tank, &MassComponent), With<ExternalTank>>,
 ) {
-    for mut tank in &mut internal_tanks {
-        // ramping
-        if throttle.0 > 0.01 {
-            tank.active = true;
-        }
+    for (mut tank, mass_component) in &mut internal_tanks {
+        let Ok(engine) = engines.get(mass_component.0) else {
+            continue;
+        };
 
-        tank.flow_rate =
-            tank.flow_rates.0 + throttle.0 / 100. * (tank.flow_rates.1 - tank.flow_rates.0);
+        // feed tank supplies the engine
+        tank.active = engine.lit;
+        tank.flow_rate = engine.fuel_flow;
     }
 
-    for mut tank in &mut external_tanks {
-        // place holder (Should be set with a switch)
-        if throttle.0 > 0.01 {
-            tank.active = true;
+    for (mut tank, mass_component) in &mut external_tanks {
+        let Ok(fuel_system) = fuel_systems.get(mass_component.0) else {
+            continue;
+        };
+
+        // gravity feed unless the tanks are pressurized
+        tank.active = fuel_system.transfer;
+        tank.flow_rate = match fuel_system.pressurized {
+            true => tank.flow_rates.1,
+            false => tank.flow_rates.0,
+        };
+    }
+}
+
+pub fn update_fuel_feed(
+    time: Res<Time>,
+    mut planes: Query<(&mut Engine, &Mass, &GForceCache, &AirData, &Transform)>,
+    internal_tanks: Query<&MassData, (With<Tank>, Without<ExternalTank>)>,
+) {
+    let dt: f32 = time.delta_secs();
+
+    for (mut engine, masses, g_force_cache, air_data, transform) in &mut planes {
+        let feed_fuel: f32 = masses
+            .0
+            .iter()
+            .filter_map(|entity| internal_tanks.get(*entity).ok())
+            .map(|mass_data| mass_data.0)
+            .sum();
+
+        // negative g unports the feed tank
+        engine.negative_g = match g_force_cache.load_factor(transform.rotation) < 0. {
+            true => engine.negative_g + dt,
+            false => (engine.negative_g - dt).max(0.),
+        };
+
+        // inlet distortion
+        engine.high_alpha = match air_data.alpha.abs() > engine.max_alpha {
+            true => engine.high_alpha + dt,
+            false => (engine.high_alpha - dt).max(0.),
+        };
+
+        engine.fuel_starved = feed_fuel <= 0.
+            || engine.negative_g > engine.negative_g_limit
+            || engine.high_alpha > engine.high_alpha_limit;
+    }
+}
+
+pub fn jettison_external_tanks(
+    mut commands: Commands,
+    planes: Query<(
+        &FuelSystem,
+        Option<&Structure>,
+        &Velocity,
+        &GlobalPosition,
+        &Transform,
+    )>,
+    tanks: Query<(Entity, &MassComponent, &MassData, &GlobalTransform), With<ExternalTank>>,
+) {
+    for (entity, mass_component, mass_data, tank_transform) in &tanks {
+        let Ok((fuel_system, structure, velocity, position, plane_transform)) =
+            planes.get(mass_component.0)
+        else {
+            continue;
+        };
+
+        // jettisoned, or torn off by an overstress
+        let torn_off: bool = structure.is_some_and(|structure| structure.tip_tanks_failed);
+
+        if !fuel_system.jettison && !torn_off {
+            continue;
         }
 
-        tank.flow_rate =
-            tank.flow_rates.0 + throttle.0 / 100. * (tank.flow_rates.1 - tank.flow_rates.0);
+        let offset: Vec3 = tank_transform.translation() - plane_transform.translation;
+        let separation: Vec3 = plane_transform.rotation * Vec3::NEG_Y * 1.5;
+
+        commands
+            .entity(entity)
+            .remove::<(ExternalFuelTankBundle, DragTarget, ChildOf)>()
+            .insert(FreeFallBundle::new(
+                mass_data.0 + TIP_TANK_DRY_MASS,
+                0.2,
+                velocity.0 + separation,
+                GlobalPosition {
+                    x: position.x + offset.x as f64,
+                    y: position.y + offset.y as f64,
+                    z: position.z + offset.z as f64,
+                },
+                tank_transform.compute_transform(),
+            ));
     }
 }
 
@@ -189,3 +311,39 @@ pub fn get_weight(masses: &Mass, mass_components: &Query<&MassData, With<MassCom
 
     mass
 }
+
+pub fn update_mass_properties(
+    mut planes: Query<(&Mass, &mut CenterOfGravity, &mut Inertia)>,
+    mass_components: Query<(&MassData, Option<&MassInertia>), With<MassComponent>>,
+) {
+    for (masses, mut center_of_gravity, mut inertia) in &mut planes {
+        let components = || {
+            masses
+                .0
+                .iter()
+                .filter_map(|entity| mass_components.get(*entity).ok())
+        };
+
+        let (total, moment) = components().fold((0., Vec3::ZERO), |(total, moment), (data, _)| {
+            (total + data.0, moment + data.1 * data.0)
+        });
+
+        if total <= 0. {
+            continue;
+        }
+
+        let center: Vec3 = moment / total;
+
+        // parallel axis theorem about the cg
+        let tensor: Mat3 = components().fold(Mat3::ZERO, |tensor, (data, local)| {
+            let r: Vec3 = data.1 - center;
+            let outer: Mat3 = Mat3::from_cols(r * r.x, r * r.y, r * r.z);
+            let point: Mat3 = (Mat3::IDENTITY * r.length_squared() - outer) * data.0;
+
+            tensor + point + local.map_or(Mat3::ZERO, |local| local.0)
+        });
+
+        center_of_gravity.0 = center;
+        inertia.0 = tensor;
+    }
+}
@@ -1,51 +1,79 @@
-use std::{
-    f32::consts::{FRAC_PI_4, PI},
-    process::Command,
-};
+use std::f32::consts::FRAC_PI_4;
 
 use bevy::{
-    app::{FixedUpdate, Plugin},
+    app::{FixedFirst, FixedLast, FixedUpdate, Plugin, PostUpdate, Update},
+    asset::{AssetApp, Assets, Handle},
     ecs::{
         bundle::Bundle,
         component::Component,
         entity::Entity,
         query::{With, Without},
-        system::{Commands, Query, Res, ResMut, Single},
+        schedule::{IntoScheduleConfigs, Schedule},
+        system::{Query, Res},
     },
-    math::{Dir3, EulerRot, Quat, Vec2, Vec3},
+    log::warn,
+    math::{Quat, Vec3},
     prelude::{Deref, DerefMut},
     time::Time,
-    transform::components::Transform,
+    transform::{TransformSystems, components::Transform},
 };
 
 use crate::{
-    cf104::{Joystick, console::throttle::Throttle},
+    cf104::Joystick,
     projectile::{
-        control_surfaces::{apply_angular_damping, update_angular_projectile_velocity},
-        drag::{CrossSectionArea, Drag, drag_force, update_cross_section},
-        engine::Engine,
-        lift::lift_force,
+        air_data::{AirData, update_air_data},
+        brakes::{Brakes, update_brakes},
+        clouds::update_in_cloud,
+        coefficients::{AeroCoefficients, AeroCoefficientsLoader, AeroModel},
+        collision::{Hull, ImpactMessage, log_impacts, update_terrain_collision},
+        control_surfaces::{
+            ControlSurfaces, update_angular_projectile_velocity, update_control_surfaces,
+        },
+        drag::{
+            CrossSectionArea, CrossSectionTable, CrossSectionTables, Drag, drag_force,
+            poll_cross_section_bake, request_cross_section_bake, update_cross_section,
+        },
+        engine::{Engine, update_engine_thrust},
+        interpolation::{
+            Interpolated, InterpolationSet, interpolate_transforms, restore_physics_state,
+            store_physics_state,
+        },
+        landing_gear::{GearLoads, LandingGear, update_gear_extension, update_landing_gear},
+        lift::{lift_force, side_force},
         mass::{
-            ExternalTank, Mass, MassBundle, MassComponent, MassData, Tank, get_weight,
-            update_fuel_mass_system, update_tank_flow_rate,
+            CenterOfGravity, FuelSystem, Inertia, Mass, MassComponent, MassData, get_weight,
+            jettison_external_tanks, update_fuel_feed, update_fuel_mass_system,
+            update_mass_properties, update_tank_flow_rate,
         },
-        util::{GRAVITY, air_density, altitude},
-        weather::{
-            Pressure, Temperature, WeatherMeta, WeatherPlugin, Wind, get_pressure, get_temperature,
-            get_wind,
+        physics::{
+            PhysicsSet, PhysicsSettings, PhysicsStep, StepSet, apply_physics_rate, run_physics_step,
         },
+        structure::{Structure, update_structure},
+        tire::RunwayCondition,
+        turbulence::{Turbulence, update_turbulence},
+        util::GRAVITY,
+        weather::WeatherPlugin,
     },
-    world::{
-        GlobalPosition, MovingOrigin,
-        util::{get_lat_f32, get_lon_f32},
-    },
+    world::{GlobalPosition, MovingOrigin},
 };
 
+pub mod air_data;
+pub mod atmosphere;
+pub mod brakes;
+pub mod clouds;
+pub mod coefficients;
+pub mod collision;
 pub mod control_surfaces;
 pub(crate) mod drag;
 pub mod engine;
+pub mod interpolation;
+pub mod landing_gear;
 pub(crate) mod lift;
 pub mod mass;
+pub mod physics;
+pub mod structure;
+pub mod tire;
+pub mod turbulence;
 pub mod util;
 
 pub mod weather;
@@ -56,17 +84,40 @@ pub struct Grounded;
 #[derive(Component, Debug)]
 pub struct Projectile;
 
+// simulation stopped for this entity
+#[derive(Component, Debug)]
+pub struct Crashed;
+
 #[derive(Component, Default, Debug)]
 pub struct GForceCache {
     pub net_force: Vec3,
     pub mass: f32,
 }
 
+impl GForceCache {
+    // normal load factor along the body up axis
+    pub fn load_factor(&self, rotation: Quat) -> f32 {
+        if self.mass <= 0. {
+            return 1.;
+        }
+
+        let specific_force: Vec3 = self.net_force / self.mass + Vec3::Y * GRAVITY;
+
+        specific_force.dot(rotation * Vec3::Y) / GRAVITY
+    }
+}
+
 #[derive(Component, Deref, DerefMut, Debug)]
 pub struct Velocity(pub Vec3);
 
 #[derive(Component, Deref, DerefMut, Debug)]
-pub struct AngularVelocity(pub Vec3);
+pub struct AngularVelocity(pub Vec3); // body frame
+
+#[derive(Component, Deref, DerefMut, Default, Debug)]
+pub struct AngularMomentum(pub Vec3); // world frame
+
+#[derive(Component, Deref, DerefMut, Default, Debug)]
+pub struct AeroMoment(pub Vec3); // body frame
 
 #[derive(Component, Debug)]
 pub struct DragCoefficient(pub f32);
@@ -77,9 +128,6 @@ pub struct GravityScale(pub f32);
 #[derive(Component, Debug)]
 pub struct WingArea(pub f32); //m^2
 
-#[derive(Component, Debug)]
-pub struct BrakeForce(pub f32, pub bool);
-
 #[derive(Component, Debug)]
 pub struct SteeringWheel {
     pub max_angle: f32,
@@ -91,7 +139,7 @@ pub struct SteeringWheel {
 #[derive(Bundle)]
 pub struct GroundedBundle {
     pub grounded: Grounded,
-    pub brake_force: BrakeForce,
+    pub brakes: Brakes,
     pub turn_radius: SteeringWheel,
 }
 
@@ -99,7 +147,7 @@ impl GroundedBundle {
     pub fn cf_104() -> Self {
         GroundedBundle {
             grounded: Grounded,
-            brake_force: BrakeForce(8_000.0, false),
+            brakes: Brakes::cf_104(),
             turn_radius: SteeringWheel {
                 max_angle: FRAC_PI_4,
                 input_dir: 0.0,
@@ -117,15 +165,33 @@ pub struct PlaneBundle {
     pub projectile: Projectile,
     pub velocity: Velocity,
     pub angular_velocity: AngularVelocity,
+    pub angular_momentum: AngularMomentum,
+    pub aero_moment: AeroMoment,
+    pub inertia: Inertia,
+    pub center_of_gravity: CenterOfGravity,
+    pub control_surfaces: ControlSurfaces,
+    pub air_data: AirData,
+    pub turbulence: Turbulence,
+    pub fuel_system: FuelSystem,
+    pub aero_model: AeroModel,
     pub mass: Mass,
     pub wing_area: WingArea,
     pub engine: Engine,
     pub drag: Drag,
     pub cross_section_area: CrossSectionArea,
+    pub landing_gear: LandingGear,
+    pub gear_loads: GearLoads,
+    pub hull: Hull,
+    pub structure: Structure,
+    pub interpolated: Interpolated,
 }
 
 impl PlaneBundle {
-    pub fn cf_104(position: Vec3) -> Self {
+    pub fn cf_104(
+        position: Vec3,
+        aero_coefficients: Handle<AeroCoefficients>,
+        turbulence_seed: u64,
+    ) -> Self {
         Self {
             position: GlobalPosition {
                 x: position.x as f64,
@@ -136,47 +202,77 @@ impl PlaneBundle {
             projectile: Projectile,
             velocity: Velocity(Vec3::ZERO),
             angular_velocity: AngularVelocity(Vec3::ZERO),
+            angular_momentum: AngularMomentum::default(),
+            aero_moment: AeroMoment::default(),
+            inertia: Inertia::cf_104(),
+            center_of_gravity: CenterOfGravity::default(),
+            control_surfaces: ControlSurfaces::default(),
+            air_data: AirData::default(),
+            turbulence: Turbulence::seeded(turbulence_seed),
+            fuel_system: FuelSystem::default(),
+            aero_model: AeroModel(aero_coefficients),
             mass: Mass::default(),
             wing_area: WingArea(18.2),
             engine: Engine::cf104(),
             drag: Drag::new(),
             cross_section_area: CrossSectionArea::default(),
+            landing_gear: LandingGear::cf_104(),
+            gear_loads: GearLoads::default(),
+            hull: Hull::cf_104(),
+            structure: Structure::cf_104(),
+            interpolated: Interpolated::default(),
         }
     }
 }
 
-fn vec3_fmt(v: Vec3) -> String {
-    format!("({:.2}, {:.2}, {:.2})", v.x, v.y, v.z)
+// released stores (drop tanks etc.)
+#[derive(Component, Debug)]
+pub struct FreeFall {
+    pub mass: f32, // kg
+    pub area: f32, // m^2
 }
 
-pub fn update_engine_thrust(
-    time: Res<Time>,
-    throttle: Single<&Throttle>,
-    mut engine_query: Query<&mut Engine>,
-) {
-    for mut engine in &mut engine_query {
-        // ramping
-        if throttle.0 > 0.05 {
-            engine.elapsed += time.delta_secs();
-        } else {
-            engine.elapsed = (engine.elapsed - time.delta_secs() * 2.0).max(0.0);
-        }
-
-        let ramp_factor = if engine.ramp_time > 0.0 {
-            (engine.elapsed / engine.ramp_time).clamp(0.0, 1.0)
-        } else {
-            1.0
-        };
+#[derive(Bundle)]
+pub struct FreeFallBundle {
+    pub projectile: Projectile,
+    pub free_fall: FreeFall,
+    pub drag_coefficient: DragCoefficient,
+    pub air_data: AirData,
+    pub velocity: Velocity,
+    pub angular_velocity: AngularVelocity,
+    pub position: GlobalPosition,
+    pub transform: Transform,
+    pub interpolated: Interpolated,
+}
 
-        engine.current_thrust = engine.max_thrust * (throttle.0 / 100.) * ramp_factor;
+impl FreeFallBundle {
+    pub fn new(
+        mass: f32,
+        area: f32,
+        velocity: Vec3,
+        position: GlobalPosition,
+        transform: Transform,
+    ) -> Self {
+        Self {
+            projectile: Projectile,
+            free_fall: FreeFall { mass, area },
+            drag_coefficient: DragCoefficient(0.3),
+            air_data: AirData::default(),
+            velocity: Velocity(velocity),
+            angular_velocity: AngularVelocity(Vec3::new(0., 0., -0.3)),
+            position,
+            transform,
+            interpolated: Interpolated::default(),
+        }
     }
 }
 
+// nosewheel steering angle, the turn itself comes from the tire forces in update_landing_gear
 pub fn update_grounded_turn(
     time: Res<Time>,
-    mut query: Query<(&Velocity, &mut Transform, &mut SteeringWheel), (With<Grounded>)>,
+    mut query: Query<(&Velocity, &mut SteeringWheel), (With<Grounded>)>,
 ) {
-    for (vel, mut transform, mut wheel) in &mut query {
+    for (vel, mut wheel) in &mut query {
         let dt = time.delta_secs();
         let speed = vel.length();
 
@@ -190,283 +286,170 @@ pub fn update_grounded_turn(
         let max_delta = wheel.delta_speed * dt;
         let applied_delta = angle_diff.clamp(-max_delta, max_delta);
         wheel.current_angle += applied_delta;
-
-        let yaw_rate = (wheel.current_angle * (1.0 - speed_factor)) * dt * 0.25;
-        let yaw_rotation = Quat::from_rotation_y(yaw_rate);
-        transform.rotation = yaw_rotation * transform.rotation;
-
-        // println!(
-        //     "Wheel Debug → speed: {:.1} m/s | eff: {:.2} | target: {:.2} rad | current: {:.2} rad | applied: {:.3}",
-        //     speed,
-        //     speed_effectiveness,
-        //     target_angle,
-        //     wheel.current_angle,
-        //     applied_delta
-        // );
     }
 }
 
-pub fn update_projectile_velocity(
-    time: Res<Time>,
-
-    //weather data
-    weather_meta: Res<WeatherMeta>,
-    wind: Res<Wind>,
-    temperature: Res<Temperature>,
-    pressure: Res<Pressure>,
+pub fn update_forces(
+    aero_assets: Res<Assets<AeroCoefficients>>,
 
     mut query: Query<
         (
-            &mut Velocity,
             &mut GForceCache,
-            &GlobalPosition,
+            &mut AeroMoment,
             &Transform,
             &Mass,
             &CrossSectionArea,
             &WingArea,
             &Engine,
+            &AirData,
+            &AeroModel,
+            &ControlSurfaces,
+            &Structure,
+            &CenterOfGravity,
+            Option<&GearLoads>,
         ),
-        (With<Projectile>, Without<Grounded>),
+        (With<Projectile>, Without<Crashed>),
     >,
     mass_components: Query<&MassData, With<MassComponent>>,
 ) {
     for (
-        mut velocity,
         mut g_force_cache,
-        position,
+        mut aero_moment,
         transform,
         masses,
         cross_section,
         wing_area,
         engine,
+        air_data,
+        aero_model,
+        surfaces,
+        structure,
+        center_of_gravity,
+        gear_loads,
     ) in &mut query
     {
-        let dt = time.delta_secs();
-
-        let forward = transform.rotation * Vec3::X;
-        let up = transform.rotation * Vec3::Y;
-
-        let speed = velocity.length();
+        let Some(coefficients) = aero_assets.get(aero_model.0.id()) else {
+            continue;
+        };
 
         // mass
         let mass: f32 = get_weight(masses, &mass_components);
 
-        // positional_data
-        let lat: f32 = get_lat_f32(position.x as f32); // get lat and lon takes in f64
-        let lon: f32 = get_lon_f32(position.z as f32);
-        let altitude: f32 = altitude(position.y as f32);
-
-        // weather data
-        let temperature: f32 = get_temperature(lat, lon, altitude, &weather_meta, &temperature);
-        let pressure: f32 =
-            get_pressure(lat, lon, altitude, &weather_meta, &pressure, &temperature);
-        let wind = get_wind(lat, lon, altitude, &weather_meta, &wind);
-
         // --- Forces ---
         let thrust = engine.thrust_vector(transform);
 
-        let drag_force = drag_force(cross_section.area, &velocity, temperature, pressure);
+        let drag_force =
+            drag_force(coefficients, air_data, cross_section.area) * structure.drag_factor();
+
+        let lift_force = lift_force(coefficients, air_data, transform.rotation, wing_area.0)
+            * structure.lift_factor();
+
+        let side_force = side_force(
+            coefficients,
+            surfaces,
+            air_data,
+            transform.rotation,
+            wing_area.0,
+        );
 
-        let lift_force = lift_force(&forward, &velocity, &up, air_density(pressure, temperature));
+        // --- CG offset ---
+        // the aero moment is taken about the body origin, gravity acts at the cg
+        let aero_force: Vec3 = thrust + drag_force + lift_force + side_force;
+        let arm: Vec3 = -center_of_gravity.0;
+        aero_moment.0 += arm.cross(transform.rotation.inverse() * aero_force);
 
         let gravity_force = Vec3::new(0.0, -mass * GRAVITY, 0.0);
 
-        let total_force = thrust + drag_force + lift_force + gravity_force;
-        let acceleration = total_force / mass;
+        let gear_force = gear_loads.map_or(Vec3::ZERO, |gear_loads| gear_loads.force);
 
-        velocity.0 += acceleration * dt;
+        let total_force = aero_force + gravity_force + gear_force;
 
-        g_force_cache.net_force = total_force.clone();
+        g_force_cache.net_force = total_force;
         g_force_cache.mass = mass;
+    }
+}
 
-        // velocity.x+= wind.0;
-        // velocity.z+= wind.1;
+// semi-implicit Euler, update_transform moves the body with the new velocity
+pub fn integrate_velocity(
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut Velocity, &GForceCache), (With<Projectile>, Without<Crashed>)>,
+) {
+    let dt = time.delta_secs();
+
+    for (entity, mut velocity, g_force_cache) in &mut query {
+        // forces have not been evaluated yet (aero data still loading)
+        if g_force_cache.mass <= 0. {
+            continue;
+        }
 
-        // let max_speed = 590.0;
-        // if vel.0.length() > max_speed {
-        //     vel.0 = vel.0.normalize() * max_speed;
-        // }
+        let acceleration: Vec3 = g_force_cache.net_force / g_force_cache.mass;
 
-        if velocity.0.x.is_nan() {
-            panic!();
+        // drop the bad step instead of poisoning the state
+        if !acceleration.is_finite() {
+            warn!(
+                "Non-finite force on {entity:?}: force {:.2}, mass {:.1}, step skipped",
+                g_force_cache.net_force, g_force_cache.mass,
+            );
+            continue;
         }
 
-        // println!(
-        //     "pos: {:#?} rot: {:#?}\n lat:{:?} lon: {:?} altitude: {:?}",
-        //     &transform.translation,
-        //     &transform.rotation.to_euler(EulerRot::XYZ),
-        //     lat,
-        //     lon,
-        //     altitude
-        // );
-        // println!(
-        //     "Velocity: {}, Total Force: {}",
-        //     vec3_fmt(velocity.0),
-        //     vec3_fmt(total_force)
-        // );
-        // println!(
-        //     "Engine Thrust: {}, Drag: {}, Gravity: {}, Lift: {}",
-        //     vec3_fmt(thrust),
-        //     vec3_fmt(drag_force),
-        //     vec3_fmt(gravity_force),
-        //     vec3_fmt(lift_force)
-        // );
+        velocity.0 += acceleration * dt;
+
+        if !velocity.0.is_finite() {
+            warn!("Non-finite velocity on {entity:?}, reset");
+            velocity.0 = Vec3::ZERO;
+        }
     }
 }
 
-pub fn update_grounded_velocity(
+pub fn update_free_fall_velocity(
     time: Res<Time>,
-    mut moving_center: ResMut<MovingOrigin>,
-
-    //weather data
-    weather_meta: Res<WeatherMeta>,
-    wind: Res<Wind>,
-    temperature: Res<Temperature>,
-    pressure: Res<Pressure>,
-
-    mut commands: Commands,
     mut query: Query<
-        (
-            Entity,
-            &mut Velocity,
-            &BrakeForce,
-            &SteeringWheel,
-            &Transform,
-            &mut GlobalPosition,
-            &Mass,
-            &CrossSectionArea,
-            &WingArea,
-            &Engine,
-        ),
-        (With<Projectile>, With<Grounded>),
+        (&mut Velocity, &FreeFall, &DragCoefficient, &AirData),
+        (With<Projectile>, Without<Crashed>),
     >,
-    mass_components: Query<&MassData, With<MassComponent>>,
 ) {
-    const ROLLING_RESISTANCE: f32 = 0.8;
-
-    for (
-        entity,
-        mut velocity,
-        brake,
-        wheel,
-        transform,
-        mut position,
-        masses,
-        cross_section,
-        wing_area,
-        engine,
-    ) in &mut query
-    {
-        let dt = time.delta_secs();
-
-        let forward = transform.rotation * Vec3::X;
-        let right = transform.rotation * Vec3::Z;
-        let up = transform.rotation * Vec3::Y;
-
-        let speed = velocity.length();
-        let velocity_dir = if speed > 0.001 {
-            velocity.normalize()
-        } else {
-            forward
-        };
-        // positional_data
-        let lat: f32 = get_lat_f32(position.x as f32); // get lat and lon takes in f64
-        let lon: f32 = get_lon_f32(position.z as f32);
-        let altitude: f32 = altitude(position.y as f32);
-
-        // weather data
-        let temperature: f32 = get_temperature(lat, lon, altitude, &weather_meta, &temperature);
-        let pressure: f32 =
-            get_pressure(lat, lon, altitude, &weather_meta, &pressure, &temperature);
-        let wind = get_wind(lat, lon, altitude, &weather_meta, &wind);
-
-        // mass
-        let mass: f32 = get_weight(masses, &mass_components);
-
-        // --- Forces ---
-        let thrust = engine.thrust_vector(transform);
-
-        let drag_force = drag_force(cross_section.area, &velocity, temperature, pressure);
-        // let drag_force =
-        //     -velocity_dir * 0.5 * AIR_DENSITY * speed * speed * drag.0 * cross_section.0;
+    let dt = time.delta_secs();
 
-        let brake_force = if brake.1 {
-            -velocity_dir * brake.0
-        } else {
-            Vec3::ZERO
+    for (mut velocity, free_fall, drag_coefficient, air_data) in &mut query {
+        let speed: f32 = air_data.true_airspeed;
+
+        let drag_force: Vec3 = match speed < 1e-3 {
+            true => Vec3::ZERO,
+            false => {
+                -air_data.airspeed / speed
+                    * air_data.dynamic_pressure
+                    * drag_coefficient.0
+                    * free_fall.area
+            }
         };
 
-        let rolling_resistance = -velocity_dir * speed * ROLLING_RESISTANCE;
+        let acceleration: Vec3 = drag_force / free_fall.mass - Vec3::Y * GRAVITY;
 
-        let lift_force = lift_force(&forward, &velocity, &up, air_density(pressure, temperature));
-
-        let gravity_force = Vec3::new(0.0, -mass * GRAVITY, 0.0);
-
-        // total force (no lateral friction)
-        let total_force =
-            thrust + drag_force + brake_force + rolling_resistance + lift_force + gravity_force;
-
-        let acceleration = total_force / mass;
         velocity.0 += acceleration * dt;
-
-        // --- Remove lateral velocity ---
-        // Project velocity onto forward vector and discard sideways (right) component
-        let forward_vel = forward.normalize() * velocity.0.dot(forward.normalize());
-        velocity.0 = forward_vel + Vec3::Y * velocity.0.dot(Vec3::Y);
-
-        // Prevent negative vertical velocity while grounded
-        velocity.y = velocity.y.max(0.0);
-
-        // velocity.x+= wind.0;
-        // velocity.z+= wind.1;
-
-        // let max_speed = 590.0;
-        // if velocity.0.length() > max_speed {
-        //     velocity.0 = vel.0.normalize() * max_speed;
-        // }
-
-        // println!(
-        //     "\nVelocity: {} | Total: {}",
-        //     vec3_fmt(velocity.0),
-        //     vec3_fmt(total_force)
-        // );
-        // println!(
-        //     "Thrust: {} | Brake: {} | RollRes: {} | Lift: {} | Drag: {} | Gravity: {}",
-        //     vec3_fmt(thrust),
-        //     vec3_fmt(brake_force),
-        //     vec3_fmt(rolling_resistance),
-        //     vec3_fmt(lift_force),
-        //     vec3_fmt(drag_force),
-        //     vec3_fmt(gravity_force),
-        // );
-
-        if velocity.y > 0.0 {
-            position.x = transform.translation.x as f64;
-            position.y = transform.translation.y as f64;
-            position.z = transform.translation.z as f64;
-
-            // TODO! - only do this if the entity is the player
-            moving_center.0 = Some(entity);
-
-            commands.entity(entity).remove::<Grounded>();
-            println!("✈️  Takeoff! The CF-104 is airborne.");
-        }
     }
 }
 
 pub fn update_transform(
     time: Res<Time>,
     center: Res<MovingOrigin>,
-    mut query: Query<(
-        &mut Transform,
-        &mut GlobalPosition,
-        &Velocity,
-        &AngularVelocity,
-    )>,
+    mut query: Query<
+        (
+            &mut Transform,
+            &mut GlobalPosition,
+            &Velocity,
+            &mut AngularVelocity,
+            Option<(&mut AngularMomentum, &Inertia, &AeroMoment)>,
+            Option<&GearLoads>,
+        ),
+        Without<Crashed>,
+    >,
 ) {
     let dt = time.delta_secs();
 
-    for (mut transform, mut position, velocity, angular_velocity) in &mut query {
+    for (mut transform, mut position, velocity, mut angular_velocity, mut rigid_body, gear_loads) in
+        &mut query
+    {
         position.x += (velocity.x * dt) as f64;
         position.y += (velocity.y * dt) as f64;
         position.z += (velocity.z * dt) as f64;
@@ -474,45 +457,246 @@ pub fn update_transform(
         if center.0.is_none() {
             transform.translation += velocity.0 * dt;
         }
-        // println!("position: {position:?}");
 
-        // transform.translation += velocity.0 * dt;
+        // --- Angular momentum ---
+        if let Some((angular_momentum, inertia, aero_moment)) = rigid_body.as_mut() {
+            let gear_moment = gear_loads.map_or(Vec3::ZERO, |gear_loads| gear_loads.moment);
+            let moment: Vec3 = aero_moment.0 + gear_moment;
 
-        let omega = angular_velocity.0;
-        if omega.length_squared() > 1e-8 {
-            let roll_angle: f32 = omega.x * dt;
-            let pitch_angle: f32 = omega.z * dt;
-            let yaw_angle: f32 = omega.y * dt;
+            // keep the last good momentum rather than spinning up on a bad moment
+            if moment.is_finite() {
+                angular_momentum.0 += transform.rotation * moment * dt;
+            }
+
+            if !angular_momentum.0.is_finite() {
+                warn!("Non-finite angular momentum, reset");
+                angular_momentum.0 = Vec3::ZERO;
+            }
 
-            let right: Dir3 = transform.forward();
-            let forward: Dir3 = transform.right();
-            let up: Dir3 = transform.up();
+            let body_momentum: Vec3 = transform.rotation.inverse() * angular_momentum.0;
+            angular_velocity.0 = inertia.0.inverse() * body_momentum;
+        }
 
-            transform.rotate_axis(forward, roll_angle);
-            transform.rotate_axis(right, pitch_angle);
-            transform.rotate_axis(up, yaw_angle);
+        if !angular_velocity.0.is_finite() {
+            warn!("Non-finite angular velocity, reset");
+            angular_velocity.0 = Vec3::ZERO;
+        }
+
+        let omega = angular_velocity.0;
+        if omega.length_squared() > 1e-8 {
+            transform.rotation =
+                (transform.rotation * Quat::from_scaled_axis(omega * dt)).normalize();
         }
     }
 }
 
-pub struct ProjectilePlugin;
+// one substep, run_physics_step replays it to fill the fixed step
+fn physics_step_schedule() -> Schedule {
+    let mut schedule = Schedule::new(PhysicsStep);
 
-impl Plugin for ProjectilePlugin {
-    fn build(&self, app: &mut bevy::app::App) {
-        app.add_plugins(WeatherPlugin).add_systems(
-            FixedUpdate,
+    schedule
+        .configure_sets(
+            (
+                StepSet::Environment,
+                StepSet::Controls,
+                StepSet::Forces,
+                StepSet::Integrate,
+                StepSet::Collision,
+            )
+                .chain(),
+        )
+        .add_systems((
             (
+                update_in_cloud,
+                update_turbulence,
+                update_air_data,
                 update_cross_section,
-                update_tank_flow_rate,
-                update_fuel_mass_system,
+            )
+                .chain()
+                .in_set(StepSet::Environment),
+            (
                 update_engine_thrust,
-                update_angular_projectile_velocity,
-                apply_angular_damping,
+                update_control_surfaces,
+                update_gear_extension,
                 update_grounded_turn,
-                update_projectile_velocity,
-                update_grounded_velocity,
+            )
+                .chain()
+                .in_set(StepSet::Controls),
+            (
+                update_landing_gear,
+                update_brakes,
+                update_structure,
+                update_angular_projectile_velocity,
+                update_forces,
+            )
+                .chain()
+                .in_set(StepSet::Forces),
+            (
+                integrate_velocity,
+                update_free_fall_velocity,
                 update_transform,
-            ),
-        );
+            )
+                .chain()
+                .in_set(StepSet::Integrate),
+            update_terrain_collision.in_set(StepSet::Collision),
+        ));
+
+    schedule
+}
+
+pub struct ProjectilePlugin;
+
+impl Plugin for ProjectilePlugin {
+    fn build(&self, app: &mut bevy::app::App) {
+        app.add_plugins(WeatherPlugin)
+            .init_asset::<AeroCoefficients>()
+            .init_asset_loader::<AeroCoefficientsLoader>()
+            .init_asset::<CrossSectionTable>()
+            .init_resource::<CrossSectionTables>()
+            .init_resource::<RunwayCondition>()
+            .init_resource::<PhysicsSettings>()
+            .add_message::<ImpactMessage>()
+            .configure_sets(FixedUpdate, (PhysicsSet::Mass, PhysicsSet::Step).chain())
+            .add_systems(
+                FixedUpdate,
+                (
+                    (
+                        update_tank_flow_rate,
+                        update_fuel_feed,
+                        update_fuel_mass_system,
+                        jettison_external_tanks,
+                        update_mass_properties,
+                    )
+                        .chain()
+                        .in_set(PhysicsSet::Mass),
+                    run_physics_step.in_set(PhysicsSet::Step),
+                ),
+            )
+            .add_schedule(physics_step_schedule())
+            .add_systems(FixedFirst, restore_physics_state)
+            .add_systems(FixedLast, store_physics_state)
+            .configure_sets(
+                PostUpdate,
+                InterpolationSet.before(TransformSystems::Propagate),
+            )
+            .add_systems(PostUpdate, interpolate_transforms.in_set(InterpolationSet))
+            .add_systems(
+                Update,
+                (
+                    apply_physics_rate,
+                    log_impacts,
+                    request_cross_section_bake,
+                    poll_cross_section_bake,
+                ),
+            );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bevy::{
+        asset::Assets,
+        ecs::{message::Messages, system::RunSystemOnce, world::World},
+        math::Vec2,
+        mesh::Mesh,
+    };
+
+    use super::*;
+    use crate::{
+        cf104::console::throttle::Throttle,
+        player::controls::KeyBindings,
+        projectile::mass::MassBundle,
+        projectile::weather::{
+            CloudCover, Humidity, Pressure, PressureLevels, SimulationClock, Temperature,
+            WeatherMeta, Wind,
+        },
+        world::ground::GroundTiles,
+    };
+
+    // a CF-104 at 1000 m and 150 m/s with the stick and throttle held
+    fn world() -> World {
+        let mut world = World::new();
+
+        world.init_resource::<Time>();
+        world.init_resource::<PhysicsSettings>();
+        world.init_resource::<RunwayCondition>();
+        world.init_resource::<MovingOrigin>();
+        world.init_resource::<GroundTiles>();
+        world.init_resource::<KeyBindings>();
+        world.init_resource::<Messages<ImpactMessage>>();
+
+        world.init_resource::<SimulationClock>();
+        world.init_resource::<WeatherMeta>();
+        world.init_resource::<Wind>();
+        world.init_resource::<Pressure>();
+        world.init_resource::<Temperature>();
+        world.init_resource::<Humidity>();
+        world.init_resource::<PressureLevels>();
+        world.init_resource::<CloudCover>();
+
+        world.init_resource::<Assets<Mesh>>();
+        world.init_resource::<Assets<CrossSectionTable>>();
+        world.init_resource::<Assets<AeroCoefficients>>();
+
+        let coefficients: AeroCoefficients =
+            ron::de::from_str(include_str!("../../assets/cf104/cf104.aero")).unwrap();
+        let aero = world
+            .resource_mut::<Assets<AeroCoefficients>>()
+            .add(coefficients);
+
+        world.spawn(Joystick(Vec2::new(0.2, 0.4)));
+        world.spawn(Throttle(80.));
+
+        let mut plane = PlaneBundle::cf_104(Vec3::new(0., 1000., 0.), aero, 7);
+        plane.velocity = Velocity(Vec3::NEG_X * 150.);
+
+        let plane = world.spawn((plane, Transform::default())).id();
+        world.spawn(MassBundle::empty_cf_104(plane));
+        world.run_system_once(update_mass_properties).unwrap();
+
+        world
+            .resource_mut::<Time>()
+            .advance_by(Duration::from_secs_f64(1. / 120.));
+        world.add_schedule(physics_step_schedule());
+
+        world
+    }
+
+    fn state_bits(world: &mut World) -> Vec<u32> {
+        let mut query = world.query_filtered::<(&Transform, &Velocity), With<Projectile>>();
+        let (transform, velocity) = query.single(world).unwrap();
+
+        [
+            transform.translation.to_array().as_slice(),
+            transform.rotation.to_array().as_slice(),
+            velocity.0.to_array().as_slice(),
+        ]
+        .concat()
+        .iter()
+        .map(|value| value.to_bits())
+        .collect()
+    }
+
+    #[test]
+    fn physics_step_is_deterministic() {
+        let run = || {
+            let mut world = world();
+
+            for _ in 0..2 {
+                world.run_schedule(PhysicsStep);
+            }
+
+            state_bits(&mut world)
+        };
+
+        let first: Vec<u32> = run();
+        let second: Vec<u32> = run();
+
+        assert_eq!(first, second);
+
+        // the step moved the aircraft, the comparison is not between two untouched states
+        assert_ne!(first, state_bits(&mut world()));
     }
 }
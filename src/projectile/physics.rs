@@ -0,0 +1,109 @@
+use std::time::Duration;
+
+use bevy::{
+    ecs::{
+        change_detection::DetectChanges,
+        query::{With, Without},
+        resource::Resource,
+        schedule::{ScheduleLabel, SystemSet},
+        system::{Res, ResMut},
+        world::World,
+    },
+    time::{Fixed, Time},
+};
+
+use crate::projectile::{AngularVelocity, Crashed, Projectile, Velocity};
+
+#[derive(Resource, Debug, Clone)]
+pub struct PhysicsSettings {
+    pub rate: f64, // Hz, fixed physics step
+
+    // a step is split when a projectile would travel or turn further than this
+    pub max_substeps: u32,
+    pub substep_distance: f32, // m
+    pub substep_angle: f32,    // rad
+}
+
+impl Default for PhysicsSettings {
+    fn default() -> Self {
+        Self {
+            rate: 120.0,
+
+            max_substeps: 8,
+            substep_distance: 2.0,
+            substep_angle: 0.05,
+        }
+    }
+}
+
+impl PhysicsSettings {
+    pub fn substeps(&self, dt: f32, max_speed: f32, max_rate: f32) -> u32 {
+        let distance: f32 = (max_speed * dt / self.substep_distance).ceil();
+        let angle: f32 = (max_rate * dt / self.substep_angle).ceil();
+
+        // NaN casts to 0 and is caught by the clamp
+        (distance.max(angle) as u32).clamp(1, self.max_substeps.max(1))
+    }
+}
+
+// FixedUpdate sets
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PhysicsSet {
+    Mass, // fuel, stores and mass properties, once per fixed step
+    Step, // runs PhysicsStep once per substep
+}
+
+// run once per substep with Time set to the substep
+#[derive(ScheduleLabel, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PhysicsStep;
+
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StepSet {
+    Environment, // turbulence, air data
+    Controls,    // surfaces, gear, steering, engine
+    Forces,      // forces and moments, nothing is integrated here
+    Integrate,   // semi-implicit Euler, velocity first then position
+    Collision,
+}
+
+pub fn apply_physics_rate(settings: Res<PhysicsSettings>, mut time: ResMut<Time<Fixed>>) {
+    if settings.is_changed() {
+        time.set_timestep_hz(settings.rate);
+    }
+}
+
+pub fn run_physics_step(world: &mut World) {
+    let fixed: Time = world.resource::<Time>().clone();
+    let settings: PhysicsSettings = world.resource::<PhysicsSettings>().clone();
+
+    let mut query = world
+        .query_filtered::<(&Velocity, &AngularVelocity), (With<Projectile>, Without<Crashed>)>();
+    let (max_speed, max_rate) = query.iter(world).fold(
+        (0f32, 0f32),
+        |(speed, rate), (velocity, angular_velocity)| {
+            (
+                speed.max(velocity.0.length()),
+                rate.max(angular_velocity.0.length()),
+            )
+        },
+    );
+
+    let substeps: u32 = settings.substeps(fixed.delta_secs(), max_speed, max_rate);
+    let step: Duration = fixed.delta() / substeps;
+
+    // replay the fixed step in equal slices, the last one lands exactly on the fixed clock
+    let mut time: Time = Time::default();
+    time.advance_to(fixed.elapsed() - fixed.delta());
+
+    for substep in 0..substeps {
+        match substep + 1 == substeps {
+            true => time.advance_to(fixed.elapsed()),
+            false => time.advance_by(step),
+        }
+
+        *world.resource_mut::<Time>() = time.clone();
+        world.run_schedule(PhysicsStep);
+    }
+
+    *world.resource_mut::<Time>() = fixed;
+}
@@ -0,0 +1,179 @@
+use bevy::{
+    ecs::{
+        component::Component,
+        entity::Entity,
+        query::{With, Without},
+        system::{Query, Res},
+    },
+    log::warn,
+    time::Time,
+    transform::components::Transform,
+};
+
+use crate::projectile::{
+    AngularVelocity, Crashed, GForceCache, Projectile,
+    control_surfaces::body_rates,
+    mass::{ExternalTank, Mass, MassComponent, MassData, get_weight},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Wing {
+    Left,
+    Right,
+}
+
+#[derive(Component, Debug)]
+pub struct Structure {
+    // design limits
+    pub limit_load: f32,          // g at design weight
+    pub negative_limit_load: f32, // g at design weight
+    pub design_weight: f32,       // kg
+    pub tip_tank_limit: f32,      // g with tip tanks fitted
+    pub ultimate_factor: f32,     // ultimate / limit load
+
+    pub overstress_tolerance: f32, // s at 2x limit load before failure
+    pub fatigue_life: f32,         // s at limit load
+
+    // state
+    pub load_factor: f32,
+    pub damage: f32,  // 0..1
+    pub fatigue: f32, // 0..1
+    pub tip_tanks_failed: bool,
+    pub failed_wing: Option<Wing>,
+}
+
+impl Structure {
+    // F-104G
+    pub fn cf_104() -> Self {
+        Self {
+            limit_load: 7.33,
+            negative_limit_load: -3.0,
+            design_weight: 8_900.0,
+            tip_tank_limit: 5.0,
+            ultimate_factor: 1.5,
+
+            overstress_tolerance: 1.0,
+            fatigue_life: 3_600.0,
+
+            load_factor: 1.0,
+            damage: 0.,
+            fatigue: 0.,
+            tip_tanks_failed: false,
+            failed_wing: None,
+        }
+    }
+
+    // (positive, negative) limit load factors, the wing root sees the same bending at n * W
+    pub fn limits(&self, weight: f32, tip_tanks: bool) -> (f32, f32) {
+        let weight_factor: f32 = (self.design_weight / weight.max(1.0)).min(1.0);
+        let fatigue_factor: f32 = 1.0 - 0.25 * self.fatigue;
+
+        let mut positive: f32 = self.limit_load * weight_factor * fatigue_factor;
+        if tip_tanks {
+            positive = positive.min(self.tip_tank_limit);
+        }
+
+        let negative: f32 = self.negative_limit_load * weight_factor * fatigue_factor;
+
+        (positive, negative)
+    }
+
+    pub fn lift_factor(&self) -> f32 {
+        let wing: f32 = match self.failed_wing {
+            Some(_) => 0.5,
+            None => 1.0,
+        };
+
+        wing * (1.0 - 0.25 * self.damage)
+    }
+
+    pub fn drag_factor(&self) -> f32 {
+        let wing: f32 = match self.failed_wing {
+            Some(_) => 0.3,
+            None => 0.,
+        };
+
+        1.0 + 0.5 * self.damage + wing
+    }
+
+    // roll coefficient per unit lift coefficient, half the lift is lost a quarter span out
+    pub fn roll_asymmetry(&self) -> f32 {
+        match self.failed_wing {
+            Some(Wing::Left) => -0.125,
+            Some(Wing::Right) => 0.125,
+            None => 0.,
+        }
+    }
+}
+
+pub fn update_structure(
+    time: Res<Time>,
+    mut planes: Query<
+        (
+            Entity,
+            &mut Structure,
+            &GForceCache,
+            &Transform,
+            &AngularVelocity,
+            &Mass,
+        ),
+        (With<Projectile>, Without<Crashed>),
+    >,
+    mass_components: Query<&MassData, With<MassComponent>>,
+    external_tanks: Query<&MassComponent, With<ExternalTank>>,
+) {
+    let dt: f32 = time.delta_secs();
+
+    for (entity, mut structure, g_force_cache, transform, angular_velocity, masses) in &mut planes {
+        if g_force_cache.mass <= 0. {
+            continue;
+        }
+
+        let weight: f32 = get_weight(masses, &mass_components);
+        let tip_tanks: bool = external_tanks.iter().any(|tank| tank.0 == entity);
+
+        let load_factor: f32 = g_force_cache.load_factor(transform.rotation);
+        structure.load_factor = load_factor;
+
+        let (positive_limit, negative_limit) = structure.limits(weight, tip_tanks);
+        let ratio: f32 = match load_factor >= 0. {
+            true => load_factor / positive_limit,
+            false => load_factor / negative_limit,
+        };
+
+        // --- Fatigue ---
+        // every excursion past half the limit load uses up some life
+        structure.fatigue = (structure.fatigue
+            + 8.0 * (ratio - 0.5).max(0.).powi(3) * dt / structure.fatigue_life)
+            .min(1.0);
+
+        // --- Overstress ---
+        if ratio > 1.0 {
+            structure.damage =
+                (structure.damage + (ratio - 1.0) * dt / structure.overstress_tolerance).min(1.0);
+        }
+
+        // --- Failures ---
+        if tip_tanks
+            && !structure.tip_tanks_failed
+            && load_factor > structure.tip_tank_limit * structure.ultimate_factor
+        {
+            structure.tip_tanks_failed = true;
+            warn!("Tip tanks torn off at {load_factor:.1} g");
+        }
+
+        if structure.failed_wing.is_none()
+            && (ratio > structure.ultimate_factor || structure.damage >= 1.0)
+        {
+            // the up going wing sees the higher angle of attack
+            let (p, _, _) = body_rates(angular_velocity.0);
+            let wing: Wing = match p > 0. {
+                true => Wing::Left,
+                false => Wing::Right,
+            };
+
+            structure.failed_wing = Some(wing);
+            warn!("{wing:?} wing failure at {load_factor:.1} g");
+        }
+    }
+}
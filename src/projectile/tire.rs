@@ -0,0 +1,94 @@
+use bevy::{ecs::resource::Resource, math::Vec3};
+
+// below this the slip angle is taken against a relaxation speed instead of the rolling speed
+const RELAXATION_SPEED: f32 = 2.0; // m/s
+
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunwayCondition {
+    #[default]
+    Dry,
+    Wet,
+    Icy,
+}
+
+impl RunwayCondition {
+    // peak tire / surface friction coefficient
+    pub fn friction(&self) -> f32 {
+        match self {
+            Self::Dry => 0.8,
+            Self::Wet => 0.4,
+            Self::Icy => 0.1,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Tire {
+    pub cornering_stiffness: f32, // lateral force per unit load per rad of slip
+    pub rolling_resistance: f32,  // fraction of load
+    pub anti_skid: f32,           // fraction of the peak friction the anti-skid holds under braking
+
+    pub deflated: bool,
+}
+
+impl Tire {
+    pub fn cf_104() -> Self {
+        Self {
+            cornering_stiffness: 8.0,
+            rolling_resistance: 0.02,
+            anti_skid: 0.85,
+
+            deflated: false,
+        }
+    }
+
+    // heading: world space rolling direction of the wheel (horizontal)
+    // velocity: world space velocity of the contact patch
+    // brake_demand: longitudinal force requested by the brakes (N)
+    // returns the contact force and the power absorbed by the brake (W)
+    pub fn force(
+        &self,
+        heading: Vec3,
+        velocity: Vec3,
+        load: f32,
+        brake_demand: f32,
+        condition: RunwayCondition,
+    ) -> (Vec3, f32) {
+        if load <= 0. {
+            return (Vec3::ZERO, 0.);
+        }
+
+        // running on the rim
+        let (cornering_stiffness, rolling_resistance) = match self.deflated {
+            true => (0.3 * self.cornering_stiffness, 0.25),
+            false => (self.cornering_stiffness, self.rolling_resistance),
+        };
+
+        let lateral: Vec3 = Vec3::Y.cross(heading);
+
+        let longitudinal_speed: f32 = velocity.dot(heading);
+        let lateral_speed: f32 = velocity.dot(lateral);
+
+        let max_friction: f32 = condition.friction() * load;
+
+        // --- Cornering ---
+        let slip_angle: f32 = lateral_speed.atan2(longitudinal_speed.abs().max(RELAXATION_SPEED));
+        let lateral_force: f32 =
+            -max_friction * (cornering_stiffness * load * slip_angle / max_friction).tanh();
+
+        // --- Rolling / braking ---
+        // fades out around standstill so the wheels hold the aircraft without chattering
+        let rolling_direction: f32 = -(longitudinal_speed / 0.5).clamp(-1.0, 1.0);
+
+        let braking: f32 = brake_demand.min(self.anti_skid * max_friction);
+        let longitudinal_force: f32 = rolling_direction * (rolling_resistance * load + braking);
+
+        // --- Friction circle ---
+        let force: Vec3 = heading * longitudinal_force + lateral * lateral_force;
+        let force: Vec3 = force.clamp_length_max(max_friction);
+
+        let brake_power: f32 = braking * (rolling_direction * longitudinal_speed).abs();
+
+        (force, brake_power)
+    }
+}
@@ -0,0 +1,169 @@
+use std::f32::consts::PI;
+
+use bevy::{
+    ecs::{
+        component::Component,
+        query::With,
+        system::{Query, Res},
+    },
+    math::Vec3,
+    time::Time,
+};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::{
+    projectile::{
+        Projectile,
+        air_data::AirData,
+        clouds::InCloud,
+        weather::{PressureLevels, SimulationClock, WeatherMeta, Wind, get_wind},
+    },
+    world::{
+        GlobalPosition,
+        util::{get_lat_f32, get_lon_f32},
+    },
+};
+
+const FEET_PER_METER: f32 = 3.28084;
+const CLOUD_SIGMA: f32 = 1.5; // m/s, light turbulence inside cloud
+
+// Dryden gust model (MIL-F-8785C scales)
+#[derive(Component, Debug)]
+pub struct Turbulence {
+    pub intensity: f32, // multiplier on the weather derived gust intensity
+    pub gust: Vec3,     // world space (m/s)
+
+    state: Vec3, // (u, v, w) along track, cross track, vertical (m/s)
+    rng: ChaCha8Rng,
+}
+
+impl Turbulence {
+    pub fn seeded(seed: u64) -> Self {
+        Self {
+            intensity: 1.0,
+            gust: Vec3::ZERO,
+            state: Vec3::ZERO,
+            rng: ChaCha8Rng::seed_from_u64(seed),
+        }
+    }
+
+    fn gaussian(&mut self) -> Vec3 {
+        let mut sample = || {
+            let u1: f32 = self.rng.random::<f32>().max(f32::EPSILON);
+            let u2: f32 = self.rng.random();
+
+            (-2.0 * u1.ln()).sqrt() * (2.0 * PI * u2).cos()
+        };
+
+        Vec3::new(sample(), sample(), sample())
+    }
+}
+
+// returns (sigma, length scale) per (u, v, w) axis in m/s and m
+fn dryden_scales(height: f32, wind_20ft: f32) -> (Vec3, Vec3) {
+    let h: f32 = (height * FEET_PER_METER).max(10.0);
+    let w20: f32 = wind_20ft * FEET_PER_METER;
+
+    // --- Low altitude (< 1000 ft) ---
+    let low = |h: f32| {
+        let k: f32 = 0.177 + 0.000823 * h;
+
+        let sigma_w: f32 = 0.1 * w20;
+        let sigma_uv: f32 = sigma_w / k.powf(0.4);
+
+        let length_w: f32 = h;
+        let length_uv: f32 = h / k.powf(1.2);
+
+        (
+            Vec3::new(sigma_uv, sigma_uv, sigma_w),
+            Vec3::new(length_uv, length_uv, length_w),
+        )
+    };
+
+    // --- Medium / high altitude (> 2000 ft) ---
+    let high = |h: f32| {
+        // light turbulence is ~5 ft/s with a 15 kt surface wind, thinning out above 9000 ft
+        let fade: f32 = 1.0 - 0.5 * ((h - 9_000.0) / 36_000.0).clamp(0.0, 1.0);
+        let sigma: f32 = 5.0 * (w20 / 25.3) * fade;
+
+        (Vec3::splat(sigma), Vec3::splat(1_750.0))
+    };
+
+    let (sigma, length) = if h <= 1_000.0 {
+        low(h)
+    } else if h >= 2_000.0 {
+        high(h)
+    } else {
+        let t: f32 = (h - 1_000.0) / 1_000.0;
+        let (sigma_low, length_low) = low(1_000.0);
+        let (sigma_high, length_high) = high(2_000.0);
+
+        (
+            sigma_low.lerp(sigma_high, t),
+            length_low.lerp(length_high, t),
+        )
+    };
+
+    (sigma / FEET_PER_METER, length / FEET_PER_METER)
+}
+
+pub fn update_turbulence(
+    time: Res<Time>,
+
+    //weather data
+    clock: Res<SimulationClock>,
+    weather_meta: Res<WeatherMeta>,
+    wind: Res<Wind>,
+    pressure_levels: Res<PressureLevels>,
+
+    mut query: Query<
+        (&mut Turbulence, &AirData, &GlobalPosition, Option<&InCloud>),
+        With<Projectile>,
+    >,
+) {
+    let dt: f32 = time.delta_secs();
+
+    for (mut turbulence, air_data, position, in_cloud) in &mut query {
+        // positional_data (world y = 0 is the Lahr field elevation)
+        let lat: f32 = get_lat_f32(position.x as f32);
+        let lon: f32 = get_lon_f32(position.z as f32);
+        let height: f32 = (position.y as f32).max(0.);
+
+        // 20 ft wind sets the turbulence intensity
+        let (u, v) = get_wind(
+            lat,
+            lon,
+            6.1,
+            clock.now(),
+            &weather_meta,
+            &wind,
+            &pressure_levels,
+        );
+        let wind_20ft: f32 = (u * u + v * v).sqrt() * turbulence.intensity;
+
+        let (sigma, length) = dryden_scales(height, wind_20ft);
+
+        // convective gusts inside cloud add to the wind driven ones
+        let cloud_sigma: f32 =
+            in_cloud.map_or(0., |cloud| CLOUD_SIGMA * cloud.density) * turbulence.intensity;
+        let sigma: Vec3 = (sigma * sigma + Vec3::splat(cloud_sigma * cloud_sigma)).powf(0.5);
+
+        // --- Dryden filters (exact first order discretisation) ---
+        let speed: f32 = air_data.true_airspeed.max(1.0);
+        let decay: Vec3 = (-speed * dt / length).exp();
+        let gain: Vec3 = sigma * (Vec3::ONE - decay * decay).max(Vec3::ZERO).powf(0.5);
+
+        let noise: Vec3 = turbulence.gaussian();
+        turbulence.state = decay * turbulence.state + gain * noise;
+
+        // --- Gust frame ---
+        let along: Vec3 = Vec3::new(air_data.airspeed.x, 0., air_data.airspeed.z)
+            .try_normalize()
+            .unwrap_or(Vec3::X);
+        let across: Vec3 = Vec3::Y.cross(along);
+
+        turbulence.gust =
+            along * turbulence.state.x + across * turbulence.state.y + Vec3::Y * turbulence.state.z;
+    }
+}
@@ -1,8 +1,17 @@
+use bevy::math::Vec3;
+
+use crate::world::util::FIELD_ELEVATION_F32;
+
 pub const GRAVITY: f32 = 9.80907; //m/s^2 (wolfram alpha)
 
+// the nose points along -x: the engine thrusts along -x, the exhaust sits at +x
+pub const BODY_FORWARD: Vec3 = Vec3::NEG_X;
+pub const BODY_UP: Vec3 = Vec3::Y;
+pub const BODY_RIGHT: Vec3 = Vec3::NEG_Z;
+
 #[inline]
 pub fn altitude(y: f32) -> f32 {
-    y + 156.
+    y + FIELD_ELEVATION_F32
 }
 
 const LAHR_LAT: f32 = 48.123;
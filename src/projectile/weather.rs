@@ -1,29 +1,31 @@
-use bevy::app::{Plugin, Startup, Update};
+use bevy::app::{FixedUpdate, Plugin, Startup, Update};
 use bevy::asset::io::Reader;
-use bevy::asset::{Asset, AssetApp, AssetLoader, AssetServer, Assets, Handle, LoadContext};
+use bevy::asset::{AssetApp, AssetLoader, AssetServer, Assets, Handle, LoadContext};
 use bevy::ecs::resource::Resource;
+use bevy::ecs::schedule::IntoScheduleConfigs;
 use bevy::ecs::system::{Res, ResMut};
-use bevy::reflect::TypePath;
+use bevy::time::Time;
 use ron::de::SpannedError;
-use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-use crate::projectile::util::{GAS_CONSTANT, GRAVITY, celsius_to_kelvin};
-
-#[derive(Asset, TypePath, Debug, Serialize, Deserialize)]
-pub struct WeatherData {
-    pub lats: Vec<f32>,
-    pub lons: Vec<f32>,
-    pub temperature_2m: Vec<f32>,
-    pub pressure_msl: Vec<f32>,
-    pub u10: Vec<f32>,
-    pub v10: Vec<f32>,
-    pub u100: Vec<f32>,
-    pub v100: Vec<f32>,
-    pub cloud_low: Vec<f32>,
-    pub cloud_mid: Vec<f32>,
-    pub cloud_high: Vec<f32>,
-}
+use crate::{
+    projectile::{
+        atmosphere::{
+            Atmosphere, AtmosphereSample, ISA_GRAVITY, TROPOPAUSE, geometric_altitude,
+            geopotential_altitude,
+        },
+        physics::PhysicsSet,
+        util::{GAS_CONSTANT, celsius_to_kelvin},
+    },
+    world::{
+        tiles::{MAGIC, TileError},
+        util::FIELD_ELEVATION_F32,
+    },
+};
+
+mod data;
+mod tiles;
+pub use data::{WeatherData, WeatherGridError};
 
 #[derive(Debug, Error)]
 pub enum WeatherDataLoaderError {
@@ -32,6 +34,12 @@ pub enum WeatherDataLoaderError {
 
     #[error("Failed to parse RON config: {0}")]
     Ron(#[from] SpannedError),
+
+    #[error("Failed to read weather tiles: {0}")]
+    Tiles(#[from] TileError),
+
+    #[error("Inconsistent weather grid: {0}")]
+    Grid(#[from] WeatherGridError),
 }
 
 #[derive(Default)]
@@ -50,7 +58,13 @@ impl AssetLoader for WeatherDataLoader {
     ) -> Result<Self::Asset, Self::Error> {
         let mut bytes = Vec::new();
         reader.read_to_end(&mut bytes).await?;
-        let data: WeatherData = ron::de::from_bytes(&bytes)?;
+        // tiled binary from tile_convert, read whole, or the RON written by weather_convert
+        let data: WeatherData = match bytes.starts_with(&MAGIC) {
+            true => WeatherData::from_tiles(&bytes)?,
+            false => ron::de::from_bytes(&bytes)?,
+        };
+
+        data.validate()?;
 
         Ok(data)
     }
@@ -70,10 +84,35 @@ impl Default for WeatherInitialized {
 
 #[derive(Resource, Default)]
 pub struct WeatherMeta {
+    times: Vec<f64>,
     lats: Vec<f32>,
     lons: Vec<f32>,
 }
 
+// drives the weather, starts at the first forecast snapshot
+#[derive(Resource, Debug)]
+pub struct SimulationClock {
+    pub start: f64,   // s since the unix epoch
+    pub elapsed: f64, // s
+    pub rate: f64,    // simulated s per real s
+}
+
+impl Default for SimulationClock {
+    fn default() -> Self {
+        Self {
+            start: 0.,
+            elapsed: 0.,
+            rate: 1.0,
+        }
+    }
+}
+
+impl SimulationClock {
+    pub fn now(&self) -> f64 {
+        self.start + self.elapsed
+    }
+}
+
 #[derive(Resource, Default)]
 pub struct Wind((Vec<f32>, Vec<f32>), (Vec<f32>, Vec<f32>));
 
@@ -83,6 +122,73 @@ pub struct Pressure(Vec<f32>);
 #[derive(Resource, Default)]
 pub struct Temperature(Vec<f32>);
 
+#[derive(Resource, Default)]
+pub struct Humidity(Vec<f32>);
+
+#[derive(Resource, Default)]
+pub struct PressureLevels {
+    levels: Vec<f32>, // hPa
+    u: Vec<f32>,
+    v: Vec<f32>,
+    temperature: Vec<f32>,
+    geopotential: Vec<f32>,
+}
+
+impl PressureLevels {
+    // [time][lat][lon] block of a level, empty when the field is missing
+    fn level<'a>(&self, data: &'a [f32], k: usize) -> &'a [f32] {
+        let n_levels = self.levels.len();
+
+        if n_levels == 0 || data.len() % n_levels != 0 {
+            return &[];
+        }
+
+        let n = data.len() / n_levels;
+        &data[k * n..(k + 1) * n]
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct LevelPoint {
+    height: f32,      // m above MSL
+    pressure: f32,    // Pa
+    temperature: f32, // K
+    wind: (f32, f32), // (u, v)
+}
+
+// levels with complete data, lowest first
+fn level_profile(
+    lat: f32,
+    lon: f32,
+    time: f64,
+    meta: &Res<WeatherMeta>,
+    levels: &Res<PressureLevels>,
+) -> Vec<LevelPoint> {
+    let mut points: Vec<LevelPoint> = (0..levels.levels.len())
+        .filter_map(|k| {
+            let field = |data: &[f32]| find(lat, lon, time, meta, levels.level(data, k)).ok();
+
+            Some(LevelPoint {
+                height: geometric_altitude(field(&levels.geopotential)? / ISA_GRAVITY),
+                pressure: levels.levels[k] * 100.0,
+                temperature: field(&levels.temperature)?,
+                wind: (field(&levels.u)?, field(&levels.v)?),
+            })
+        })
+        .collect();
+
+    points.sort_by(|a, b| a.height.total_cmp(&b.height));
+    points
+}
+
+// hydrostatic pressure dh above a point, mean layer temperature
+#[inline]
+fn hypsometric(base_pressure: f32, base_temperature: f32, temperature: f32, dh: f32) -> f32 {
+    let mean_temperature: f32 = 0.5 * (base_temperature + temperature);
+
+    base_pressure * (-ISA_GRAVITY * dh / (GAS_CONSTANT * mean_temperature)).exp()
+}
+
 #[derive(Resource, Default)]
 pub struct CloudCover {
     low: Vec<f32>,
@@ -90,19 +196,84 @@ pub struct CloudCover {
     high: Vec<f32>,
 }
 
-pub(super) fn find(lat: f32, lon: f32, meta: &Res<WeatherMeta>, data: &[f32]) -> Result<f32, ()> {
+// (lower snapshot, weight of the next one), clamped to the forecast range
+fn time_index(times: &[f64], time: f64) -> (usize, f32) {
+    let n_time = times.len();
+
+    if n_time < 2 || time <= times[0] {
+        return (0, 0.);
+    }
+    if time >= times[n_time - 1] {
+        return (n_time - 1, 0.);
+    }
+
+    let i = times.partition_point(|t| *t <= time).saturating_sub(1);
+    let span = (times[i + 1] - times[i]).max(f64::EPSILON);
+
+    (i, ((time - times[i]) / span) as f32)
+}
+
+pub(super) fn find(
+    lat: f32,
+    lon: f32,
+    time: f64,
+    meta: &Res<WeatherMeta>,
+    data: &[f32],
+) -> Result<f32, WeatherGridError> {
+    let n_grid = meta.lats.len() * meta.lons.len();
+    let n_time = meta.times.len().max(1);
+
+    if data.len() != n_time * n_grid {
+        return Err(WeatherGridError::MismatchedGrid {
+            expected: n_time * n_grid,
+            found: data.len(),
+        });
+    }
+
+    let (i, t) = time_index(&meta.times, time);
+
+    let f0 = find_grid(lat, lon, meta, &data[i * n_grid..(i + 1) * n_grid])?;
+    if t <= 0. {
+        return Ok(f0);
+    }
+
+    let f1 = find_grid(lat, lon, meta, &data[(i + 1) * n_grid..(i + 2) * n_grid])?;
+
+    Ok(f0 + (f1 - f0) * t)
+}
+
+// bilinear lookup in a single snapshot
+fn find_grid(
+    lat: f32,
+    lon: f32,
+    meta: &Res<WeatherMeta>,
+    data: &[f32],
+) -> Result<f32, WeatherGridError> {
     let lats = &meta.lats;
     let lons = &meta.lons;
 
     let n_lat = lats.len();
     let n_lon = lons.len();
 
-    if n_lat < 2 || n_lon < 2 {
-        return Err(()); // not enough data to interpolate
+    // not enough data to interpolate
+    if n_lat < 2 {
+        return Err(WeatherGridError::TooFewPoints {
+            axis: "lats",
+            found: n_lat,
+        });
+    }
+    if n_lon < 2 {
+        return Err(WeatherGridError::TooFewPoints {
+            axis: "lons",
+            found: n_lon,
+        });
     }
 
     if data.len() != n_lat * n_lon {
-        return Err(()); // mismatched data grid
+        return Err(WeatherGridError::MismatchedGrid {
+            expected: n_lat * n_lon,
+            found: data.len(),
+        });
     }
 
     // --- Clamp lat/lon to grid range ---
@@ -149,59 +320,126 @@ pub(super) fn find(lat: f32, lon: f32, meta: &Res<WeatherMeta>, data: &[f32]) ->
     Ok(value)
 }
 
-pub fn get_temperature(
+// ISA deviation from the 2 m temperature, the grid is assumed to sit at the field elevation
+pub fn get_atmosphere(
     lat: f32,
     lon: f32,
-    altitude: f32,
+    time: f64,
     meta: &Res<WeatherMeta>,
     temperature: &Res<Temperature>,
-) -> f32 {
+    pressure: &Res<Pressure>,
+    humidity: &Res<Humidity>,
+) -> Atmosphere {
     const DEFAULT_SURFACE_TEMP: f32 = 30.0;
+    const DEFAULT_SURFACE_PRESSURE: f32 = 101_325.0;
+    const DEFAULT_RELATIVE_HUMIDITY: f32 = 0.0; // %
+
+    let surface_temperature: f32 = celsius_to_kelvin(
+        find(lat, lon, time, meta, &temperature.0).unwrap_or(DEFAULT_SURFACE_TEMP),
+    );
+    let standard_temperature: f32 = Atmosphere::standard().temperature(FIELD_ELEVATION_F32);
 
-    let surface_temperature: f32 =
-        celsius_to_kelvin(find(lat, lon, meta, &temperature.0).unwrap_or(DEFAULT_SURFACE_TEMP));
+    let sea_level_pressure: f32 =
+        find(lat, lon, time, meta, &pressure.0).unwrap_or(DEFAULT_SURFACE_PRESSURE);
 
-    if altitude <= 11_000.0 {
-        surface_temperature - 0.0065 * altitude
-    } else {
-        216.65
+    let relative_humidity: f32 =
+        find(lat, lon, time, meta, &humidity.0).unwrap_or(DEFAULT_RELATIVE_HUMIDITY) / 100.0;
+
+    Atmosphere {
+        temperature_offset: surface_temperature - standard_temperature,
+        sea_level_pressure,
+        relative_humidity,
     }
 }
 
-pub fn get_pressure(
+// temperature and pressure from the pressure levels, ISA + dT where they are missing
+pub fn sample_atmosphere(
     lat: f32,
     lon: f32,
-    altitude: f32,
+    altitude: f32, // m above MSL
+    time: f64,
     meta: &Res<WeatherMeta>,
+    temperature: &Res<Temperature>,
     pressure: &Res<Pressure>,
-    temperature: &f32,
-) -> f32 {
-    const DEFAULT_SURFACE_PRESSURE: f32 = 101_325.0;
-
-    let surface_pressure_pa: f32 =
-        find(lat, lon, meta, &pressure.0).unwrap_or(DEFAULT_SURFACE_PRESSURE);
+    humidity: &Res<Humidity>,
+    levels: &Res<PressureLevels>,
+) -> AtmosphereSample {
+    let atmosphere: Atmosphere =
+        get_atmosphere(lat, lon, time, meta, temperature, pressure, humidity);
+
+    let profile: Vec<LevelPoint> = level_profile(lat, lon, time, meta, levels)
+        .into_iter()
+        .filter(|point| point.height > FIELD_ELEVATION_F32)
+        .collect();
+
+    let (Some(lowest), Some(highest)) = (profile.first(), profile.last()) else {
+        return atmosphere.sample(altitude);
+    };
 
-    // let temperature: f32 = get_temperature(lat, lon, altitude, meta, temperature);
+    let (temperature, pressure) = if altitude >= highest.height {
+        // standard lapse rates above the top level, matched to it
+        let top = Atmosphere {
+            temperature_offset: highest.temperature
+                - Atmosphere::standard().temperature(highest.height),
+            ..atmosphere
+        };
+
+        (
+            top.temperature(altitude),
+            highest.pressure * top.pressure(altitude) / top.pressure(highest.height),
+        )
+    } else if altitude < lowest.height {
+        // surface to the lowest level above it
+        let surface_temperature: f32 = atmosphere.temperature(FIELD_ELEVATION_F32);
+        let t: f32 = (altitude - FIELD_ELEVATION_F32) / (lowest.height - FIELD_ELEVATION_F32);
+        let temperature: f32 = surface_temperature + (lowest.temperature - surface_temperature) * t;
+
+        (
+            temperature,
+            hypsometric(
+                lowest.pressure,
+                lowest.temperature,
+                temperature,
+                altitude - lowest.height,
+            ),
+        )
+    } else {
+        let (below, above) = profile
+            .windows(2)
+            .map(|pair| (pair[0], pair[1]))
+            .find(|(_, above)| altitude < above.height)
+            .unwrap_or((*lowest, *highest));
+
+        let t: f32 = (altitude - below.height) / (above.height - below.height).max(1.0);
+        let temperature: f32 = below.temperature + (above.temperature - below.temperature) * t;
+
+        (
+            temperature,
+            hypsometric(
+                below.pressure,
+                below.temperature,
+                temperature,
+                altitude - below.height,
+            ),
+        )
+    };
 
-    const LAPSE_RATE: f32 = 0.0065; // K/m
+    let relative_humidity: f32 = match geopotential_altitude(altitude) < TROPOPAUSE {
+        true => atmosphere.relative_humidity,
+        false => 0.,
+    };
 
-    if altitude <= 11_000.0 {
-        surface_pressure_pa
-            * (1.0 - LAPSE_RATE * altitude / temperature)
-                .powf(GRAVITY / (GAS_CONSTANT * LAPSE_RATE))
-    } else {
-        let pressure_at_11km: f32 = surface_pressure_pa
-            * (1.0 - LAPSE_RATE * 11_000.0 / 288.15).powf(GRAVITY / (GAS_CONSTANT * LAPSE_RATE));
-        pressure_at_11km * (-GRAVITY * (altitude - 11_000.0) / (GAS_CONSTANT * temperature)).exp()
-    }
+    AtmosphereSample::new(temperature, pressure, relative_humidity)
 }
 
 pub fn get_wind(
     lat: f32,
     lon: f32,
-    altitude: f32,
+    altitude: f32, // m above the field
+    time: f64,
     meta: &Res<WeatherMeta>,
     wind: &Res<Wind>,
+    levels: &Res<PressureLevels>,
 ) -> (f32, f32) {
     let (low_wind, high_wind) = (&wind.0, &wind.1);
 
@@ -209,10 +447,40 @@ pub fn get_wind(
     let (u_100m_data, v_100m_data) = (&high_wind.0, &high_wind.1);
 
     // --- Interpolate base values for 10 m and 100 m levels ---
-    let u10 = find(lat, lon, meta, u_10m_data).unwrap_or(0.);
-    let v10 = find(lat, lon, meta, v_10m_data).unwrap_or(0.);
-    let u100 = find(lat, lon, meta, u_100m_data).unwrap_or(0.);
-    let v100 = find(lat, lon, meta, v_100m_data).unwrap_or(0.);
+    let u10 = find(lat, lon, time, meta, u_10m_data).unwrap_or(0.);
+    let v10 = find(lat, lon, time, meta, v_10m_data).unwrap_or(0.);
+    let u100 = find(lat, lon, time, meta, u_100m_data).unwrap_or(0.);
+    let v100 = find(lat, lon, time, meta, v_100m_data).unwrap_or(0.);
+
+    // --- Pressure levels ---
+    // linear in height from the 100 m wind up through the levels, held above the top one
+    if altitude > 100.0 {
+        let profile: Vec<(f32, (f32, f32))> = std::iter::once((100.0, (u100, v100)))
+            .chain(
+                level_profile(lat, lon, time, meta, levels)
+                    .into_iter()
+                    .map(|point| (point.height - FIELD_ELEVATION_F32, point.wind))
+                    .filter(|(height, _)| *height > 100.0),
+            )
+            .collect();
+
+        if profile.len() > 1 {
+            let Some((below, above)) = profile
+                .windows(2)
+                .map(|pair| (pair[0], pair[1]))
+                .find(|(_, above)| altitude < above.0)
+            else {
+                return profile[profile.len() - 1].1;
+            };
+
+            let t: f32 = (altitude - below.0) / (above.0 - below.0).max(1.0);
+
+            return (
+                below.1.0 + t * (above.1.0 - below.1.0),
+                below.1.1 + t * (above.1.1 - below.1.1),
+            );
+        }
+    }
 
     // --- Compute horizontal wind at requested altitude ---
     let (u, v) = if altitude <= 10.0 {
@@ -230,6 +498,44 @@ pub fn get_wind(
     (u, v)
 }
 
+// (low, mid, high) cloud cover fractions
+pub fn get_cloud_cover(
+    lat: f32,
+    lon: f32,
+    time: f64,
+    meta: &Res<WeatherMeta>,
+    cloud_cover: &Res<CloudCover>,
+) -> (f32, f32, f32) {
+    (
+        find(lat, lon, time, meta, &cloud_cover.low).unwrap_or(0.),
+        find(lat, lon, time, meta, &cloud_cover.mid).unwrap_or(0.),
+        find(lat, lon, time, meta, &cloud_cover.high).unwrap_or(0.),
+    )
+}
+
+// lifting condensation level (m above the field) from the 2 m temperature and dew point
+pub fn get_cloud_base(
+    lat: f32,
+    lon: f32,
+    time: f64,
+    meta: &Res<WeatherMeta>,
+    temperature: &Res<Temperature>,
+    humidity: &Res<Humidity>,
+) -> Option<f32> {
+    let celsius: f32 = find(lat, lon, time, meta, &temperature.0).ok()?;
+    let relative_humidity: f32 = find(lat, lon, time, meta, &humidity.0).ok()? / 100.0;
+
+    // Magnus dew point
+    let gamma: f32 = relative_humidity.clamp(0.01, 1.0).ln() + 17.27 * celsius / (237.3 + celsius);
+    let dew_point: f32 = 237.3 * gamma / (17.27 - gamma);
+
+    Some(125.0 * (celsius - dew_point).max(0.))
+}
+
+fn advance_simulation_clock(time: Res<Time>, mut clock: ResMut<SimulationClock>) {
+    clock.elapsed += time.delta_secs_f64() * clock.rate;
+}
+
 fn load_weather_data(
     asset_server: Res<AssetServer>,
 
@@ -249,7 +555,10 @@ fn initialize_weather(
     mut wind: ResMut<Wind>,
     mut pressure: ResMut<Pressure>,
     mut temperature: ResMut<Temperature>,
+    mut humidity: ResMut<Humidity>,
     mut cloud_cover: ResMut<CloudCover>,
+    mut pressure_levels: ResMut<PressureLevels>,
+    mut clock: ResMut<SimulationClock>,
 ) {
     if weather_intialized.1 {
         return;
@@ -266,6 +575,7 @@ fn initialize_weather(
 
     temperature.0 = data.temperature_2m.clone();
     pressure.0 = data.pressure_msl.clone();
+    humidity.0 = data.relative_humidity_2m.clone();
 
     wind.0 = (
         data.u10.iter().cloned().collect(),
@@ -281,9 +591,18 @@ fn initialize_weather(
     cloud_cover.mid = data.cloud_mid.clone();
     cloud_cover.high = data.cloud_high.clone();
 
+    pressure_levels.levels = data.levels.clone();
+    pressure_levels.u = data.level_u.clone();
+    pressure_levels.v = data.level_v.clone();
+    pressure_levels.temperature = data.level_temperature.clone();
+    pressure_levels.geopotential = data.level_geopotential.clone();
+
+    meta.times = data.times.clone();
     meta.lats = data.lats.clone();
     meta.lons = data.lons.clone();
 
+    clock.start = data.times.first().copied().unwrap_or(0.);
+
     weather_intialized.0 = None;
     weather_intialized.1 = true;
 }
@@ -299,8 +618,15 @@ impl Plugin for WeatherPlugin {
             .init_resource::<Wind>()
             .init_resource::<Pressure>()
             .init_resource::<Temperature>()
+            .init_resource::<Humidity>()
             .init_resource::<CloudCover>()
+            .init_resource::<PressureLevels>()
+            .init_resource::<SimulationClock>()
             .add_systems(Startup, load_weather_data)
-            .add_systems(Update, initialize_weather);
+            .add_systems(Update, initialize_weather)
+            .add_systems(
+                FixedUpdate,
+                advance_simulation_clock.before(PhysicsSet::Mass),
+            );
     }
 }
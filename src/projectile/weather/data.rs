@@ -0,0 +1,123 @@
+// shared with the weather_convert tool, keep this free of game code
+use bevy::{asset::Asset, reflect::TypePath};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+// every field is laid out as [time][lat][lon], a file without times is a single snapshot
+#[derive(Asset, TypePath, Debug, Serialize, Deserialize)]
+pub struct WeatherData {
+    #[serde(default)]
+    pub times: Vec<f64>, // s since the unix epoch, ascending
+    pub lats: Vec<f32>,
+    pub lons: Vec<f32>,
+    pub temperature_2m: Vec<f32>, // C
+    pub pressure_msl: Vec<f32>,   // Pa
+    #[serde(default)]
+    pub relative_humidity_2m: Vec<f32>, // %
+    pub u10: Vec<f32>,
+    pub v10: Vec<f32>,
+    pub u100: Vec<f32>,
+    pub v100: Vec<f32>,
+    pub cloud_low: Vec<f32>, // 0..1
+    pub cloud_mid: Vec<f32>,
+    pub cloud_high: Vec<f32>,
+
+    // optional pressure level fields, laid out as [level][time][lat][lon]
+    #[serde(default)]
+    pub levels: Vec<f32>, // hPa
+    #[serde(default)]
+    pub level_u: Vec<f32>, // m/s
+    #[serde(default)]
+    pub level_v: Vec<f32>, // m/s
+    #[serde(default)]
+    pub level_temperature: Vec<f32>, // K
+    #[serde(default)]
+    pub level_geopotential: Vec<f32>, // m^2/s^2
+}
+
+#[derive(Debug, Error)]
+pub enum WeatherGridError {
+    #[error("Axis `{axis}` needs at least 2 points, found {found}")]
+    TooFewPoints { axis: &'static str, found: usize },
+
+    #[error("Axis `{0}` must be strictly ascending")]
+    UnsortedAxis(&'static str),
+
+    #[error("Grid expected {expected} values, found {found}")]
+    MismatchedGrid { expected: usize, found: usize },
+
+    #[error("Field `{field}` expected {expected} values, found {found}")]
+    MismatchedField {
+        field: &'static str,
+        expected: usize,
+        found: usize,
+    },
+}
+
+impl WeatherData {
+    // the lookups fall back to defaults on a bad field, catch it once at load instead
+    pub fn validate(&self) -> Result<(), WeatherGridError> {
+        let ascending_f32 = |axis: &[f32]| axis.windows(2).all(|pair| pair[0] < pair[1]);
+        let ascending_f64 = |axis: &[f64]| axis.windows(2).all(|pair| pair[0] < pair[1]);
+
+        for (axis, len) in [("lats", self.lats.len()), ("lons", self.lons.len())] {
+            if len < 2 {
+                return Err(WeatherGridError::TooFewPoints { axis, found: len });
+            }
+        }
+
+        if !ascending_f32(&self.lats) {
+            return Err(WeatherGridError::UnsortedAxis("lats"));
+        }
+        if !ascending_f32(&self.lons) {
+            return Err(WeatherGridError::UnsortedAxis("lons"));
+        }
+        if !ascending_f64(&self.times) {
+            return Err(WeatherGridError::UnsortedAxis("times"));
+        }
+
+        let n_surface: usize = self.times.len().max(1) * self.lats.len() * self.lons.len();
+        let n_levels: usize = self.levels.len() * n_surface;
+
+        let check = |field: &'static str, data: &[f32], expected: usize, optional: bool| {
+            if data.len() == expected || (optional && data.is_empty()) {
+                return Ok(());
+            }
+
+            Err(WeatherGridError::MismatchedField {
+                field,
+                expected,
+                found: data.len(),
+            })
+        };
+
+        check("temperature_2m", &self.temperature_2m, n_surface, false)?;
+        check("pressure_msl", &self.pressure_msl, n_surface, false)?;
+        check("u10", &self.u10, n_surface, false)?;
+        check("v10", &self.v10, n_surface, false)?;
+        check("u100", &self.u100, n_surface, false)?;
+        check("v100", &self.v100, n_surface, false)?;
+
+        check(
+            "relative_humidity_2m",
+            &self.relative_humidity_2m,
+            n_surface,
+            true,
+        )?;
+        check("cloud_low", &self.cloud_low, n_surface, true)?;
+        check("cloud_mid", &self.cloud_mid, n_surface, true)?;
+        check("cloud_high", &self.cloud_high, n_surface, true)?;
+
+        check("level_u", &self.level_u, n_levels, true)?;
+        check("level_v", &self.level_v, n_levels, true)?;
+        check("level_temperature", &self.level_temperature, n_levels, true)?;
+        check(
+            "level_geopotential",
+            &self.level_geopotential,
+            n_levels,
+            true,
+        )?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,313 @@
+// shared with the tile_convert tool, keep this free of game code
+//
+// weather tiles split the grid only, every tile carries all times and levels of its
+// cells. Weather only uses the container (magic, version, kind), the loader stitches
+// every tile back into one dense WeatherData: the grid is coarse and the lookups
+// interpolate across all of it, so nothing is streamed the way GroundTiles is
+use std::ops::Range;
+
+use crate::{
+    projectile::weather::WeatherData,
+    world::tiles::{ByteReader, ByteWriter, TileError, TileHeader, TileKind},
+};
+
+// every field with whether it is on the pressure levels, the bit order of the field mask
+fn fields(data: &WeatherData) -> [(&Vec<f32>, bool); 14] {
+    [
+        (&data.temperature_2m, false),
+        (&data.pressure_msl, false),
+        (&data.relative_humidity_2m, false),
+        (&data.u10, false),
+        (&data.v10, false),
+        (&data.u100, false),
+        (&data.v100, false),
+        (&data.cloud_low, false),
+        (&data.cloud_mid, false),
+        (&data.cloud_high, false),
+        (&data.level_u, true),
+        (&data.level_v, true),
+        (&data.level_temperature, true),
+        (&data.level_geopotential, true),
+    ]
+}
+
+fn fields_mut(data: &mut WeatherData) -> [(&mut Vec<f32>, bool); 14] {
+    [
+        (&mut data.temperature_2m, false),
+        (&mut data.pressure_msl, false),
+        (&mut data.relative_humidity_2m, false),
+        (&mut data.u10, false),
+        (&mut data.v10, false),
+        (&mut data.u100, false),
+        (&mut data.v100, false),
+        (&mut data.cloud_low, false),
+        (&mut data.cloud_mid, false),
+        (&mut data.cloud_high, false),
+        (&mut data.level_u, true),
+        (&mut data.level_v, true),
+        (&mut data.level_temperature, true),
+        (&mut data.level_geopotential, true),
+    ]
+}
+
+impl WeatherData {
+    pub fn from_tiles(bytes: &[u8]) -> Result<Self, TileError> {
+        let header: TileHeader = TileHeader::decode(bytes, TileKind::Weather)?;
+
+        // --- Axes ---
+        let mut extra = ByteReader::new(&header.extra);
+        let times: Vec<f64> = extra.f64s()?;
+        let levels: Vec<f32> = extra.f32s()?;
+        let lats: Vec<f32> = extra.f32s()?;
+        let lons: Vec<f32> = extra.f32s()?;
+        let mask: u32 = extra.u32()?;
+
+        let (n_lat, n_lon) = (lats.len(), lons.len());
+        let n_grid: usize = n_lat * n_lon;
+        let n_times: usize = times.len().max(1);
+        let n_levels: usize = levels.len();
+
+        let mut data = WeatherData {
+            times,
+            lats,
+            lons,
+            temperature_2m: Vec::new(),
+            pressure_msl: Vec::new(),
+            relative_humidity_2m: Vec::new(),
+            u10: Vec::new(),
+            v10: Vec::new(),
+            u100: Vec::new(),
+            v100: Vec::new(),
+            cloud_low: Vec::new(),
+            cloud_mid: Vec::new(),
+            cloud_high: Vec::new(),
+            levels,
+            level_u: Vec::new(),
+            level_v: Vec::new(),
+            level_temperature: Vec::new(),
+            level_geopotential: Vec::new(),
+        };
+
+        let mut present: Vec<(&mut Vec<f32>, usize)> = fields_mut(&mut data)
+            .into_iter()
+            .enumerate()
+            .filter(|(bit, _)| mask & (1 << bit) != 0)
+            .map(|(_, (field, on_levels))| {
+                let outer: usize = match on_levels {
+                    true => n_levels * n_times,
+                    false => n_times,
+                };
+                *field = vec![0.; outer * n_grid];
+                (field, outer)
+            })
+            .collect();
+
+        // --- Stitch ---
+        let mut covered: Vec<bool> = vec![false; n_grid];
+
+        for entry in &header.entries {
+            let mut reader = ByteReader::new(header.payload(bytes, entry)?);
+
+            let lat_start: usize = reader.u32()? as usize;
+            let lat_len: usize = reader.u32()? as usize;
+            let lon_start: usize = reader.u32()? as usize;
+            let lon_len: usize = reader.u32()? as usize;
+
+            if lat_start + lat_len > n_lat || lon_start + lon_len > n_lon {
+                return Err(TileError::Invalid("weather tile outside the grid"));
+            }
+
+            for (field, outer) in &mut present {
+                for o in 0..*outer {
+                    for i in lat_start..lat_start + lat_len {
+                        let start: usize = o * n_grid + i * n_lon + lon_start;
+                        let row: &[u8] = reader.bytes(lon_len * 4)?;
+
+                        for (value, chunk) in field[start..start + lon_len]
+                            .iter_mut()
+                            .zip(row.chunks_exact(4))
+                        {
+                            *value = f32::from_le_bytes(chunk.try_into().unwrap());
+                        }
+                    }
+                }
+            }
+
+            for i in lat_start..lat_start + lat_len {
+                covered[i * n_lon + lon_start..i * n_lon + lon_start + lon_len].fill(true);
+            }
+        }
+
+        if !covered.into_iter().all(|cell| cell) {
+            return Err(TileError::Invalid("weather tiles leave gaps in the grid"));
+        }
+
+        Ok(data)
+    }
+
+    // only the tile_convert tool writes tiles, expects validated data
+    #[allow(dead_code)]
+    pub fn to_tiles(&self, tile_size: f64) -> Vec<u8> {
+        let (n_lat, n_lon) = (self.lats.len(), self.lons.len());
+        let n_grid: usize = n_lat * n_lon;
+
+        let mut header = TileHeader {
+            kind: TileKind::Weather,
+            lat_range: (self.lats[0] as f64, self.lats[n_lat - 1] as f64),
+            lon_range: (self.lons[0] as f64, self.lons[n_lon - 1] as f64),
+            tile_size,
+            entries: Vec::new(),
+            extra: Vec::new(),
+        };
+
+        // --- Axes ---
+        let present: Vec<&Vec<f32>> = fields(self)
+            .into_iter()
+            .map(|(field, _)| field)
+            .filter(|field| !field.is_empty())
+            .collect();
+        let mask: u32 = fields(self)
+            .iter()
+            .enumerate()
+            .filter(|(_, (field, _))| !field.is_empty())
+            .fold(0, |mask, (bit, _)| mask | 1 << bit);
+
+        let mut extra = ByteWriter::default();
+        extra.f64s(&self.times);
+        extra.f32s(&self.levels);
+        extra.f32s(&self.lats);
+        extra.f32s(&self.lons);
+        extra.u32(mask);
+        header.extra = extra.0;
+
+        // --- Tiles ---
+        // consecutive grid rows falling in the same tile
+        let bands = |axis: &[f32], tile: &dyn Fn(f64) -> i32| {
+            let mut bands: Vec<(i32, Range<usize>)> = Vec::new();
+
+            for (i, value) in axis.iter().enumerate() {
+                let index: i32 = tile(*value as f64);
+
+                match bands.last_mut() {
+                    Some((last, range)) if *last == index => range.end = i + 1,
+                    _ => bands.push((index, i..i + 1)),
+                }
+            }
+
+            bands
+        };
+
+        let lat_bands = bands(&self.lats, &|lat| header.tile_of(lat, header.lon_range.0).0);
+        let lon_bands = bands(&self.lons, &|lon| header.tile_of(header.lat_range.0, lon).1);
+
+        let mut tiles: Vec<((i32, i32), Vec<u8>)> = Vec::new();
+
+        for (lat_tile, lat_range) in &lat_bands {
+            for (lon_tile, lon_range) in &lon_bands {
+                let mut payload = ByteWriter::default();
+                payload.u32(lat_range.start as u32);
+                payload.u32(lat_range.len() as u32);
+                payload.u32(lon_range.start as u32);
+                payload.u32(lon_range.len() as u32);
+
+                for field in &present {
+                    for o in 0..field.len() / n_grid {
+                        for i in lat_range.clone() {
+                            let start: usize = o * n_grid + i * n_lon;
+
+                            field[start + lon_range.start..start + lon_range.end]
+                                .iter()
+                                .for_each(|value| payload.bytes(&value.to_le_bytes()));
+                        }
+                    }
+                }
+
+                tiles.push(((*lat_tile, *lon_tile), payload.0));
+            }
+        }
+
+        header.encode(tiles)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // two times and levels on a 5x7 grid, humidity and the level temperature missing
+    fn weather() -> WeatherData {
+        let (n_times, n_levels, n_grid) = (2, 2, 5 * 7);
+        let field = |seed: f32, len: usize| -> Vec<f32> {
+            (0..len).map(|i| seed + i as f32 * 0.5).collect()
+        };
+
+        WeatherData {
+            times: vec![1_700_000_000.0, 1_700_003_600.0],
+            lats: vec![47.0, 47.25, 47.5, 47.75, 48.0],
+            lons: vec![6.0, 6.25, 6.5, 6.75, 7.0, 7.25, 7.5],
+            temperature_2m: field(10.0, n_times * n_grid),
+            pressure_msl: field(101_000.0, n_times * n_grid),
+            relative_humidity_2m: Vec::new(),
+            u10: field(-3.0, n_times * n_grid),
+            v10: field(2.0, n_times * n_grid),
+            u100: field(-4.0, n_times * n_grid),
+            v100: field(3.0, n_times * n_grid),
+            cloud_low: field(0.1, n_times * n_grid),
+            cloud_mid: field(0.2, n_times * n_grid),
+            cloud_high: field(0.3, n_times * n_grid),
+            levels: vec![850.0, 500.0],
+            level_u: field(12.0, n_levels * n_times * n_grid),
+            level_v: field(-8.0, n_levels * n_times * n_grid),
+            level_temperature: Vec::new(),
+            level_geopotential: field(14_000.0, n_levels * n_times * n_grid),
+        }
+    }
+
+    #[test]
+    fn weather_round_trip() {
+        let original = weather();
+
+        // several tiles in both directions, some of them a single row wide
+        let bytes: Vec<u8> = original.to_tiles(0.3);
+        let header = TileHeader::decode(&bytes, TileKind::Weather).unwrap();
+        assert!(header.entries.len() > 4);
+
+        let decoded = WeatherData::from_tiles(&bytes).unwrap();
+
+        assert_eq!(decoded.times, original.times);
+        assert_eq!(decoded.levels, original.levels);
+        assert_eq!(decoded.lats, original.lats);
+        assert_eq!(decoded.lons, original.lons);
+
+        for ((decoded, _), (original, _)) in fields(&decoded).into_iter().zip(fields(&original)) {
+            assert_eq!(decoded, original);
+        }
+    }
+
+    #[test]
+    fn weather_single_tile() {
+        let original = weather();
+        let decoded = WeatherData::from_tiles(&original.to_tiles(10.0)).unwrap();
+
+        assert_eq!(decoded.level_u, original.level_u);
+    }
+
+    #[test]
+    fn weather_rejects_gaps() {
+        let bytes: Vec<u8> = weather().to_tiles(0.3);
+
+        // drop the first tile
+        let header = TileHeader::decode(&bytes, TileKind::Weather).unwrap();
+        let tiles: Vec<((i32, i32), Vec<u8>)> = header
+            .entries
+            .iter()
+            .skip(1)
+            .map(|entry| (entry.tile, header.payload(&bytes, entry).unwrap().to_vec()))
+            .collect();
+
+        assert!(matches!(
+            WeatherData::from_tiles(&header.clone().encode(tiles)),
+            Err(TileError::Invalid(_))
+        ));
+    }
+}
@@ -0,0 +1,241 @@
+// shared with the tile_convert tool, keep this free of game code
+use serde::{Deserialize, Serialize};
+
+use crate::world::tiles::{ByteReader, ByteWriter, TileError};
+
+// CORINE land cover classes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LandCover {
+    ContinuousUrbanFabric = 111,
+    DiscontinuousUrbanFabric,
+    IndustrialOrCommercialUnits = 121,
+    RoadAndRailNetworksAndAssociatedLand,
+    PortAreas,
+    Airports,
+    MineralExtractionSites = 131,
+    DumpSites,
+    ConstructionSites,
+    GreenUrbanAreas = 141,
+    SportAndLeisureFacilities,
+    NonIrrigatedArableLand = 211,
+    PermanentlyIrrigatedLand,
+    RiceFields,
+    Vineyards = 221,
+    FruitTreesAndBerryPlantations,
+    OliveGroves,
+    Pastures = 231,
+    AnnualCropsAssociatedWithPermanentCrops = 241,
+    ComplexCultivationPatterns,
+    LandPrincipallyOccupiedByAgricultureWithSignificantAreasOfNaturalVegetation,
+    AgroForestryAreas,
+    BroadLeavedForest = 311,
+    ConiferousForest,
+    MixedForest,
+    NaturalGrasslands = 321,
+    MoorsAndHeathland,
+    SclerophyllousVegetation,
+    TransitionalWoodlandShrub,
+    BeachesDunesSands = 331,
+    BareRocks,
+    SparselyVegetatedAreas,
+    BurntAreas,
+    GlaciersAndPerpetualSnow,
+    InlandMarshes = 411,
+    PeatBogs,
+    SaltMarshes = 421,
+    Salines,
+    IntertidalFlats,
+    WaterCourses = 511,
+    WaterBodies,
+    CoastalLagoons = 521,
+    Estuaries,
+    SeaAndOcean,
+    Nodata = 999,
+    UnclassifiedLandSurface = 990,
+    UnclassifiedWaterBodies = 995,
+}
+
+impl LandCover {
+    const ALL: [LandCover; 47] = [
+        LandCover::ContinuousUrbanFabric,
+        LandCover::DiscontinuousUrbanFabric,
+        LandCover::IndustrialOrCommercialUnits,
+        LandCover::RoadAndRailNetworksAndAssociatedLand,
+        LandCover::PortAreas,
+        LandCover::Airports,
+        LandCover::MineralExtractionSites,
+        LandCover::DumpSites,
+        LandCover::ConstructionSites,
+        LandCover::GreenUrbanAreas,
+        LandCover::SportAndLeisureFacilities,
+        LandCover::NonIrrigatedArableLand,
+        LandCover::PermanentlyIrrigatedLand,
+        LandCover::RiceFields,
+        LandCover::Vineyards,
+        LandCover::FruitTreesAndBerryPlantations,
+        LandCover::OliveGroves,
+        LandCover::Pastures,
+        LandCover::AnnualCropsAssociatedWithPermanentCrops,
+        LandCover::ComplexCultivationPatterns,
+        LandCover::LandPrincipallyOccupiedByAgricultureWithSignificantAreasOfNaturalVegetation,
+        LandCover::AgroForestryAreas,
+        LandCover::BroadLeavedForest,
+        LandCover::ConiferousForest,
+        LandCover::MixedForest,
+        LandCover::NaturalGrasslands,
+        LandCover::MoorsAndHeathland,
+        LandCover::SclerophyllousVegetation,
+        LandCover::TransitionalWoodlandShrub,
+        LandCover::BeachesDunesSands,
+        LandCover::BareRocks,
+        LandCover::SparselyVegetatedAreas,
+        LandCover::BurntAreas,
+        LandCover::GlaciersAndPerpetualSnow,
+        LandCover::InlandMarshes,
+        LandCover::PeatBogs,
+        LandCover::SaltMarshes,
+        LandCover::Salines,
+        LandCover::IntertidalFlats,
+        LandCover::WaterCourses,
+        LandCover::WaterBodies,
+        LandCover::CoastalLagoons,
+        LandCover::Estuaries,
+        LandCover::SeaAndOcean,
+        LandCover::Nodata,
+        LandCover::UnclassifiedLandSurface,
+        LandCover::UnclassifiedWaterBodies,
+    ];
+
+    pub fn from_code(code: u16) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|land_cover| *land_cover as u16 == code)
+    }
+}
+
+// one tile of the ground grid, overlapping its neighbours by a row and a column so
+// every lookup inside the tile has its surrounding grid points
+pub struct GroundTile {
+    pub lats: Vec<f64>,
+    pub lons: Vec<f64>,
+    pub height: Vec<f32>, // m above MSL, laid out as [lat][lon]
+    pub land_use: Vec<LandCover>,
+}
+
+impl GroundTile {
+    pub fn decode(bytes: &[u8]) -> Result<Self, TileError> {
+        let mut reader = ByteReader::new(bytes);
+
+        let lats: Vec<f64> = reader.f64s()?;
+        let lons: Vec<f64> = reader.f64s()?;
+        let height: Vec<f32> = reader.f32s()?;
+        let land_use: Vec<LandCover> = reader
+            .u16s()?
+            .into_iter()
+            .map(|code| LandCover::from_code(code).ok_or(TileError::Invalid("unknown land cover")))
+            .collect::<Result<_, _>>()?;
+
+        let ascending = |axis: &[f64]| axis.windows(2).all(|pair| pair[0] < pair[1]);
+
+        if lats.len() < 2 || lons.len() < 2 {
+            return Err(TileError::Invalid("ground tile needs at least 2x2 points"));
+        }
+        if !ascending(&lats) || !ascending(&lons) {
+            return Err(TileError::Invalid(
+                "ground tile axes must be strictly ascending",
+            ));
+        }
+        if height.len() != lats.len() * lons.len() || land_use.len() != height.len() {
+            return Err(TileError::Invalid("ground tile grid size mismatch"));
+        }
+
+        Ok(Self {
+            lats,
+            lons,
+            height,
+            land_use,
+        })
+    }
+
+    // only the tile_convert tool writes tiles
+    #[allow(dead_code)]
+    pub fn encode(&self) -> Vec<u8> {
+        let mut writer = ByteWriter::default();
+
+        writer.f64s(&self.lats);
+        writer.f64s(&self.lons);
+        writer.f32s(&self.height);
+        writer.u16s(
+            &self
+                .land_use
+                .iter()
+                .map(|land_cover| *land_cover as u16)
+                .collect::<Vec<u16>>(),
+        );
+
+        writer.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tile() -> GroundTile {
+        GroundTile {
+            lats: vec![48.0, 48.5, 49.0],
+            lons: vec![7.0, 7.25],
+            height: vec![150.0, 152.5, -3.0, 160.0, 410.25, 0.0],
+            land_use: vec![
+                LandCover::ContinuousUrbanFabric,
+                LandCover::Vineyards,
+                LandCover::SeaAndOcean,
+                LandCover::ConiferousForest,
+                LandCover::Nodata,
+                LandCover::WaterBodies,
+            ],
+        }
+    }
+
+    #[test]
+    fn ground_tile_round_trip() {
+        let original = tile();
+        let decoded = GroundTile::decode(&original.encode()).unwrap();
+
+        assert_eq!(decoded.lats, original.lats);
+        assert_eq!(decoded.lons, original.lons);
+        assert_eq!(decoded.height, original.height);
+        assert_eq!(decoded.land_use, original.land_use);
+    }
+
+    #[test]
+    fn land_cover_codes_round_trip() {
+        for land_cover in LandCover::ALL {
+            assert_eq!(LandCover::from_code(land_cover as u16), Some(land_cover));
+        }
+        assert_eq!(LandCover::from_code(0), None);
+    }
+
+    #[test]
+    fn ground_tile_rejects_bad_grids() {
+        let mut unsorted = tile();
+        unsorted.lats.swap(0, 1);
+        assert!(matches!(
+            GroundTile::decode(&unsorted.encode()),
+            Err(TileError::Invalid(_))
+        ));
+
+        let mut short = tile();
+        short.height.pop();
+        assert!(matches!(
+            GroundTile::decode(&short.encode()),
+            Err(TileError::Invalid(_))
+        ));
+
+        let bytes: Vec<u8> = tile().encode();
+        assert!(matches!(
+            GroundTile::decode(&bytes[..bytes.len() - 1]),
+            Err(TileError::Truncated)
+        ));
+    }
+}
@@ -1,106 +1,79 @@
-use std::process::Command;
+use std::path::Path;
 
 use bevy::{
     app::{Plugin, Startup, Update},
     asset::{
-        io::Reader, Asset, AssetApp, AssetLoader, AssetServer, Assets, Handle, LoadContext, RenderAssetUsages
+        Asset, AssetApp, AssetLoader, AssetServer, Assets, AsyncReadExt, Handle, LoadContext,
+        RenderAssetUsages,
+        io::{
+            AssetReaderError, AssetSourceId, AsyncSeekForwardExt, MissingAssetSourceError, Reader,
+        },
     },
     camera::visibility::Visibility,
     color::Color,
     ecs::{
-        component::Component, entity::Entity, hierarchy::Children, message::MessageWriter, query::{With, Without}, resource::Resource, system::{Commands, Query, Res, ResMut}
+        component::Component,
+        entity::Entity,
+        hierarchy::Children,
+        message::MessageWriter,
+        query::{With, Without},
+        resource::Resource,
+        schedule::IntoScheduleConfigs,
+        system::{Commands, Query, Res, ResMut, SystemParam},
     },
     math::Vec3,
     mesh::{Indices, Mesh, Mesh3d, PrimitiveTopology},
     pbr::{MeshMaterial3d, StandardMaterial},
-    platform::collections::HashSet,
+    platform::collections::{HashMap, HashSet},
     reflect::TypePath,
+    tasks::{IoTaskPool, Task, block_on, poll_once},
     transform::components::{GlobalTransform, Transform},
 };
-use ron::de::SpannedError;
-use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-use crate::{cf104::Plane, player::Player, world::{
-    props::{Prop, SpawnPropsMessage}, util::{get_lat_f64, get_lon_f64}, GlobalPosition, MovingOrigin
-}};
+use crate::{
+    cf104::Plane,
+    player::Player,
+    world::{
+        GlobalPosition, MovingOrigin,
+        props::{Prop, SpawnPropsMessage},
+        tiles::{HEADER_LEN, TileError, TileHeader, TileKind},
+        util::{FIELD_ELEVATION_F32, FIELD_ELEVATION_F64, get_lat_f64, get_lon_f64},
+    },
+};
+
+mod format;
+pub use format::{GroundTile, LandCover};
 
 const GRID_SIZE: f64 = 5_000.;
 
 const MAX_VISION: f64 = 40_000.;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-pub enum LandCover {
-    ContinuousUrbanFabric = 111,
-    DiscontinuousUrbanFabric,
-    IndustrialOrCommercialUnits = 121,
-    RoadAndRailNetworksAndAssociatedLand,
-    PortAreas,
-    Airports,
-    MineralExtractionSites = 131,
-    DumpSites,
-    ConstructionSites,
-    GreenUrbanAreas = 141,
-    SportAndLeisureFacilities,
-    NonIrrigatedArableLand = 211,
-    PermanentlyIrrigatedLand,
-    RiceFields,
-    Vineyards = 221,
-    FruitTreesAndBerryPlantations,
-    OliveGroves,
-    Pastures = 231,
-    AnnualCropsAssociatedWithPermanentCrops = 241,
-    ComplexCultivationPatterns,
-    LandPrincipallyOccupiedByAgricultureWithSignificantAreasOfNaturalVegetation,
-    AgroForestryAreas,
-    BroadLeavedForest = 311,
-    ConiferousForest,
-    MixedForest,
-    NaturalGrasslands = 321,
-    MoorsAndHeathland,
-    SclerophyllousVegetation,
-    TransitionalWoodlandShrub,
-    BeachesDunesSands = 331,
-    BareRocks,
-    SparselyVegetatedAreas,
-    BurntAreas,
-    GlaciersAndPerpetualSnow,
-    InlandMarshes = 411,
-    PeatBogs,
-    SaltMarshes = 421,
-    Salines,
-    IntertidalFlats,
-    WaterCourses = 511,
-    WaterBodies,
-    CoastalLagoons = 521,
-    Estuaries,
-    SeaAndOcean,
-    Nodata = 999,
-    UnclassifiedLandSurface = 990,
-    UnclassifiedWaterBodies = 995,
-}
+const GROUND_PATH: &str = "world\\europe.ground";
 
-#[derive(Default, Serialize, Deserialize, TypePath, Asset)]
-pub struct GroundData {
-    pub lats: Vec<f64>,
-    pub lons: Vec<f64>,
-    pub height: Vec<f32>,
-    pub land_use: Vec<LandCover>,
-}
+// only the tile index, the tiles are read on demand by load_ground_tiles
+#[derive(TypePath, Asset)]
+pub struct GroundIndex(TileHeader);
 
 #[derive(Debug, Error)]
 pub enum GroundDataLoaderError {
     #[error("IO error while reading file: {0}")]
     Io(#[from] std::io::Error),
 
-    #[error("Failed to parse RON config: {0}")]
-    Ron(#[from] SpannedError),
+    #[error("Failed to read ground tiles: {0}")]
+    Tiles(#[from] TileError),
+
+    #[error("Missing asset source: {0}")]
+    Source(#[from] MissingAssetSourceError),
+
+    #[error("Failed to open ground tiles: {0}")]
+    Reader(#[from] AssetReaderError),
 }
 
 #[derive(Default)]
 pub struct GroundDataLoader;
 impl AssetLoader for GroundDataLoader {
-    type Asset = GroundData;
+    type Asset = GroundIndex;
     type Settings = ();
     type Error = GroundDataLoaderError;
 
@@ -110,11 +83,15 @@ impl AssetLoader for GroundDataLoader {
         _settings: &(),
         load_context: &mut LoadContext<'_>,
     ) -> Result<Self::Asset, Self::Error> {
-        let mut bytes = Vec::new();
-        reader.read_to_end(&mut bytes).await?;
-        let ground_data: GroundData = ron::de::from_bytes(&bytes)?;
+        let mut fixed = [0; HEADER_LEN];
+        reader.read_exact(&mut fixed).await?;
+        let (mut index, rest) = TileHeader::decode_fixed(&fixed, TileKind::Ground)?;
+
+        let mut bytes = vec![0; rest];
+        reader.read_exact(&mut bytes).await?;
+        index.decode_rest(&bytes)?;
 
-        Ok(ground_data)
+        Ok(GroundIndex(index))
     }
     fn extensions(&self) -> &[&str] {
         &["ground"]
@@ -122,19 +99,33 @@ impl AssetLoader for GroundDataLoader {
 }
 
 #[derive(Resource, Debug, Default)]
-pub struct WorldDataInitialized(Option<Handle<GroundData>>, bool);
-
-#[derive(Resource, Debug, Default)]
-pub struct GroundMeta {
-    pub lats: Vec<f64>,
-    pub lons: Vec<f64>,
+pub struct WorldDataInitialized(Option<Handle<GroundIndex>>, bool);
+
+// the tiles under the chunk window, everything else stays on disk
+#[derive(Resource, Default)]
+pub struct GroundTiles {
+    index: Option<TileHeader>,
+    loaded: HashMap<(i32, i32), GroundTile>,
+    pending: HashMap<(i32, i32), Task<Result<GroundTile, GroundDataLoaderError>>>,
+    failed: HashSet<(i32, i32)>,
 }
 
-#[derive(Resource, Debug, Default)]
-pub struct HeightData(Vec<f32>);
+impl GroundTiles {
+    fn tile(&self, lat: f64, lon: f64) -> Option<&GroundTile> {
+        let index: &TileHeader = self.index.as_ref()?;
 
-#[derive(Resource, Debug, Default)]
-pub struct LandCoverData(Vec<LandCover>);
+        self.loaded.get(&index.tile_of(lat, lon))
+    }
+
+    // m above MSL, None while the tile is not loaded
+    pub fn height(&self, lat: f64, lon: f64) -> Option<f32> {
+        Some(find(lat, lon, self.tile(lat, lon)?))
+    }
+
+    pub fn land_cover(&self, lat: f64, lon: f64) -> Option<LandCover> {
+        Some(find_nearest_land_cover(lat, lon, self.tile(lat, lon)?))
+    }
+}
 
 #[derive(Resource, Debug, Default)]
 pub struct FreeGroundChunks(Vec<Entity>);
@@ -142,23 +133,60 @@ pub struct FreeGroundChunks(Vec<Entity>);
 #[derive(Component, Debug, Default)]
 pub struct GroundChunk(i32, i32);
 
+#[derive(SystemParam)]
+pub struct Terrain<'w> {
+    ground_tiles: Res<'w, GroundTiles>,
+}
+
+impl Terrain<'_> {
+    // world space height of the rendered ground mesh, None until the tile is loaded
+    pub fn height(&self, x: f64, z: f64) -> Option<f32> {
+        let chunk_x: f64 = (x / GRID_SIZE).floor();
+        let chunk_z: f64 = (z / GRID_SIZE).floor();
+
+        let corner = |i: f64, j: f64| {
+            self.ground_tiles.height(
+                get_lat_f64((chunk_x + i) * GRID_SIZE),
+                get_lon_f64((chunk_z + j) * GRID_SIZE),
+            )
+        };
+        let height_00: f32 = corner(0., 0.)?;
+        let height_10: f32 = corner(1., 0.)?;
+        let height_01: f32 = corner(0., 1.)?;
+        let height_11: f32 = corner(1., 1.)?;
+
+        let u: f32 = (x / GRID_SIZE - chunk_x) as f32;
+        let v: f32 = (z / GRID_SIZE - chunk_z) as f32;
+
+        // same triangle split as create_height_quad_mesh
+        let height: f32 = match u + v <= 1. {
+            true => height_00 + u * (height_10 - height_00) + v * (height_01 - height_00),
+            false => {
+                height_11 + (1. - u) * (height_01 - height_11) + (1. - v) * (height_10 - height_11)
+            }
+        };
+
+        Some(height - FIELD_ELEVATION_F32)
+    }
+}
+
 pub struct GroundPlugin;
 impl Plugin for GroundPlugin {
     fn build(&self, app: &mut bevy::app::App) {
-        app.init_asset::<GroundData>()
+        app.init_asset::<GroundIndex>()
             .init_asset_loader::<GroundDataLoader>()
             .init_resource::<WorldDataInitialized>()
-            .init_resource::<GroundMeta>()
-            .init_resource::<HeightData>()
-            .init_resource::<LandCoverData>()
+            .init_resource::<GroundTiles>()
             .init_resource::<FreeGroundChunks>()
             .add_systems(Startup, load_ground_data)
-            .add_systems(Update, (
-                initialize_ground_data,
-                update_ground,
-                // update_ground_visibility
-            ));
-        // .add_systems(Update, update_ground);
+            .add_systems(
+                Update,
+                (
+                    initialize_ground_data,
+                    load_ground_tiles.before(update_ground),
+                    update_ground,
+                ),
+            );
     }
 }
 
@@ -166,7 +194,7 @@ pub fn load_ground_data(
     asset_server: Res<AssetServer>,
     mut world_data_initialize: ResMut<WorldDataInitialized>,
 ) {
-    let handle: Handle<GroundData> = asset_server.load("world\\europe.ground");
+    let handle: Handle<GroundIndex> = asset_server.load(GROUND_PATH);
 
     world_data_initialize.0 = Some(handle);
 }
@@ -174,11 +202,9 @@ pub fn load_ground_data(
 pub fn initialize_ground_data(
     mut world_data_initialize: ResMut<WorldDataInitialized>,
 
-    ground_assets: Res<Assets<GroundData>>,
+    ground_assets: Res<Assets<GroundIndex>>,
 
-    mut meta: ResMut<GroundMeta>,
-    mut height_data: ResMut<HeightData>,
-    mut land_cover: ResMut<LandCoverData>,
+    mut ground_tiles: ResMut<GroundTiles>,
 ) {
     if world_data_initialize.1 {
         return;
@@ -188,27 +214,141 @@ pub fn initialize_ground_data(
         return;
     }
 
-    let handle: Handle<GroundData> = world_data_initialize.0.clone().unwrap();
+    let handle: Handle<GroundIndex> = world_data_initialize.0.clone().unwrap();
 
-    let Some(data) = ground_assets.get(handle.id()) else {
+    let Some(GroundIndex(index)) = ground_assets.get(handle.id()) else {
         return;
     };
 
-    meta.lats = data.lats.clone();
-    meta.lons = data.lons.clone();
-
-    height_data.0 = data.height.iter().cloned().map(|x| x.max(0.0)).collect();
-    land_cover.0 = data.land_use.clone();
+    ground_tiles.index = Some(index.clone());
 
     world_data_initialize.1 = true;
     world_data_initialize.0 = None;
 }
 
+// reads the tiles under the chunk window in the background and drops the ones left behind
+pub fn load_ground_tiles(
+    moving_origin: Res<MovingOrigin>,
+    centered_entity: Query<&GlobalPosition, Without<GroundChunk>>,
+
+    asset_server: Res<AssetServer>,
+    mut ground_tiles: ResMut<GroundTiles>,
+) {
+    let GroundTiles {
+        index,
+        loaded,
+        pending,
+        failed,
+    } = &mut *ground_tiles;
+
+    let Some(index) = index.as_ref() else {
+        return;
+    };
+
+    let center: GlobalPosition = match moving_origin.0 {
+        Some(entity) => {
+            let Ok(center) = centered_entity.get(entity) else {
+                return;
+            };
+
+            *center
+        }
+        None => GlobalPosition::zero(),
+    };
+
+    // chunk corners reach one grid step past the window
+    let (min_x, max_x, min_y, max_y) = chunk_window(&center);
+    let (min_lat, min_lon) = index.tile_of(
+        get_lat_f64(min_x as f64 * GRID_SIZE),
+        get_lon_f64(min_y as f64 * GRID_SIZE),
+    );
+    let (max_lat, max_lon) = index.tile_of(
+        get_lat_f64((max_x + 1) as f64 * GRID_SIZE),
+        get_lon_f64((max_y + 1) as f64 * GRID_SIZE),
+    );
+    let wanted = |tile: &(i32, i32)| {
+        (min_lat..=max_lat).contains(&tile.0) && (min_lon..=max_lon).contains(&tile.1)
+    };
+
+    // --- Finished reads ---
+    let finished: Vec<((i32, i32), Result<GroundTile, GroundDataLoaderError>)> = pending
+        .iter_mut()
+        .filter_map(|(tile, task)| block_on(poll_once(task)).map(|result| (*tile, result)))
+        .collect();
+
+    for (tile, result) in finished {
+        pending.remove(&tile);
+
+        match result {
+            Ok(data) => {
+                loaded.insert(tile, data);
+            }
+            Err(error) => {
+                println!("⚠️  Failed to load ground tile {tile:?}: {error}");
+                failed.insert(tile);
+            }
+        }
+    }
+
+    // dropping a task cancels its read
+    loaded.retain(|tile, _| wanted(tile));
+    pending.retain(|tile, _| wanted(tile));
+
+    // --- New reads ---
+    for lat_tile in min_lat..=max_lat {
+        for lon_tile in min_lon..=max_lon {
+            let tile: (i32, i32) = (lat_tile, lon_tile);
+
+            if loaded.contains_key(&tile) || pending.contains_key(&tile) || failed.contains(&tile) {
+                continue;
+            }
+
+            let Some(entry) = index.entry(tile).copied() else {
+                println!("⚠️  Ground tile {tile:?} is missing from the index.");
+                failed.insert(tile);
+                continue;
+            };
+
+            let asset_server: AssetServer = asset_server.clone();
+            let task = IoTaskPool::get().spawn(async move {
+                let source = asset_server.get_source(AssetSourceId::Default)?;
+                let mut reader = source.reader().read(Path::new(GROUND_PATH)).await?;
+
+                reader.seek_forward(entry.offset).await?;
+                let mut bytes = vec![0; entry.len as usize];
+                reader.read_exact(&mut bytes).await?;
+
+                let mut data: GroundTile = GroundTile::decode(&bytes)?;
+                data.height
+                    .iter_mut()
+                    .for_each(|height| *height = height.max(0.0));
+
+                Ok(data)
+            });
+
+            pending.insert(tile, task);
+        }
+    }
+}
+
+// chunk range kept around the moving origin
+fn chunk_window(center: &GlobalPosition) -> (i32, i32, i32, i32) {
+    let radius: f64 = (MAX_VISION * MAX_VISION).sqrt();
+    let min_x: i32 = (round_to_nearest_grid_coord(center.x - radius, GRID_SIZE) / GRID_SIZE) as i32;
+    let max_x: i32 = (round_to_nearest_grid_coord(center.x + radius, GRID_SIZE) / GRID_SIZE) as i32;
+    let min_y: i32 = (round_to_nearest_grid_coord(center.z - radius, GRID_SIZE) / GRID_SIZE) as i32;
+    let max_y: i32 = (round_to_nearest_grid_coord(center.z + radius, GRID_SIZE) / GRID_SIZE) as i32;
+
+    (min_x, max_x, min_y, max_y)
+}
+
 fn update_ground_visibility(
     plane_query: Query<&GlobalTransform, (With<Plane>, With<Player>)>,
-    mut ground_query: Query<(&GlobalTransform, &mut Visibility), With<GroundChunk>>
+    mut ground_query: Query<(&GlobalTransform, &mut Visibility), With<GroundChunk>>,
 ) {
-    let Ok(plane_transform) = plane_query.single() else { return; };
+    let Ok(plane_transform) = plane_query.single() else {
+        return;
+    };
 
     let forward: Vec3 = *plane_transform.right();
 
@@ -236,9 +376,7 @@ pub fn update_ground(
     centered_entity: Query<&GlobalPosition, Without<GroundChunk>>,
 
     world_data_initialize: Res<WorldDataInitialized>,
-    ground_meta: Res<GroundMeta>,
-    height_data: Res<HeightData>,
-    land_cover: Res<LandCoverData>,
+    ground_tiles: Res<GroundTiles>,
 
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
@@ -246,7 +384,13 @@ pub fn update_ground(
 
     mut free_chunks: ResMut<FreeGroundChunks>,
 
-    mut ground_chunks_query: Query<(Entity, &mut GlobalPosition, &mut GroundChunk, &mut Mesh3d, &Children)>,
+    mut ground_chunks_query: Query<(
+        Entity,
+        &mut GlobalPosition,
+        &mut GroundChunk,
+        &mut Mesh3d,
+        &Children,
+    )>,
     prop_query: Query<Entity, With<Prop>>,
 
     mut spawn_prop_writer: MessageWriter<SpawnPropsMessage>,
@@ -268,11 +412,7 @@ pub fn update_ground(
     };
     center.y = 0.;
 
-    let radius: f64 = (MAX_VISION * MAX_VISION).sqrt();
-    let min_x: i32 = (round_to_nearest_grid_coord(center.x - radius, GRID_SIZE) / GRID_SIZE) as i32;
-    let max_x: i32 = (round_to_nearest_grid_coord(center.x + radius, GRID_SIZE) / GRID_SIZE) as i32;
-    let min_y: i32 = (round_to_nearest_grid_coord(center.z - radius, GRID_SIZE) / GRID_SIZE) as i32;
-    let max_y: i32 = (round_to_nearest_grid_coord(center.z + radius, GRID_SIZE) / GRID_SIZE) as i32;
+    let (min_x, max_x, min_y, max_y) = chunk_window(&center);
 
     // remove values
     for i in (0..free_chunks.0.len()).rev() {
@@ -296,8 +436,6 @@ pub fn update_ground(
         }
     }
 
-
-
     // println!("active chunks{:?}", active_chunks.len());
 
     {
@@ -308,65 +446,30 @@ pub fn update_ground(
                     continue;
                 }
 
-                let height_00: f32 = find(
-                    get_lat_f64(x as f64 * GRID_SIZE) as f64,
-                    get_lon_f64(y as f64 * GRID_SIZE) as f64,
-                    &ground_meta,
-                    &height_data.0,
-                )
-                .unwrap();
-                let height_10: f32 = find(
-                    get_lat_f64((x + 1) as f64 * GRID_SIZE) as f64,
-                    get_lon_f64(y as f64 * GRID_SIZE) as f64,
-                    &ground_meta,
-                    &height_data.0,
-                )
-                .unwrap();
-                let height_01: f32 = find(
-                    get_lat_f64(x as f64 * GRID_SIZE) as f64,
-                    get_lon_f64((y + 1) as f64 * GRID_SIZE) as f64,
-                    &ground_meta,
-                    &height_data.0,
-                )
-                .unwrap();
-                let height_11: f32 = find(
-                    get_lat_f64((x + 1) as f64 * GRID_SIZE) as f64,
-                    get_lon_f64((y + 1) as f64 * GRID_SIZE) as f64,
-                    &ground_meta,
-                    &height_data.0,
-                )
-                .unwrap();
-
-                let land_use: [LandCover; 4] = [
-                    find_nearest_land_cover(
-                        get_lat_f64(x as f64 * GRID_SIZE) as f64,
-                        get_lon_f64(y as f64 * GRID_SIZE) as f64,
-                        &ground_meta,
-                        &land_cover.0,
-                    )
-                    .unwrap(),
-                    find_nearest_land_cover(
-                        get_lat_f64((x + 1) as f64 * GRID_SIZE) as f64,
-                        get_lon_f64(y as f64 * GRID_SIZE) as f64,
-                        &ground_meta,
-                        &land_cover.0,
-                    )
-                    .unwrap(),
-                    find_nearest_land_cover(
-                        get_lat_f64(x as f64 * GRID_SIZE) as f64,
-                        get_lon_f64((y + 1) as f64 * GRID_SIZE) as f64,
-                        &ground_meta,
-                        &land_cover.0,
-                    )
-                    .unwrap(),
-                    find_nearest_land_cover(
-                        get_lat_f64((x + 1) as f64 * GRID_SIZE) as f64,
-                        get_lon_f64((y + 1) as f64 * GRID_SIZE) as f64,
-                        &ground_meta,
-                        &land_cover.0,
-                    )
-                    .unwrap(),
-                ];
+                let lat_0: f64 = get_lat_f64(x as f64 * GRID_SIZE);
+                let lat_1: f64 = get_lat_f64((x + 1) as f64 * GRID_SIZE);
+                let lon_0: f64 = get_lon_f64(y as f64 * GRID_SIZE);
+                let lon_1: f64 = get_lon_f64((y + 1) as f64 * GRID_SIZE);
+
+                // the tiles under this chunk are still loading, build it once they arrive
+                let (Some(height_00), Some(height_10), Some(height_01), Some(height_11)) = (
+                    ground_tiles.height(lat_0, lon_0),
+                    ground_tiles.height(lat_1, lon_0),
+                    ground_tiles.height(lat_0, lon_1),
+                    ground_tiles.height(lat_1, lon_1),
+                ) else {
+                    continue;
+                };
+
+                let (Some(land_use_00), Some(land_use_10), Some(land_use_01), Some(land_use_11)) = (
+                    ground_tiles.land_cover(lat_0, lon_0),
+                    ground_tiles.land_cover(lat_1, lon_0),
+                    ground_tiles.land_cover(lat_0, lon_1),
+                    ground_tiles.land_cover(lat_1, lon_1),
+                ) else {
+                    continue;
+                };
+                let land_use: [LandCover; 4] = [land_use_00, land_use_10, land_use_01, land_use_11];
 
                 // if free_chunks.0.len() > 0 {
                 //     println!("{free_chunks:?}");
@@ -383,7 +486,7 @@ pub fn update_ground(
 
                         *position = GlobalPosition {
                             x: x as f64 * GRID_SIZE,
-                            y: min_height as f64,
+                            y: min_height as f64 - FIELD_ELEVATION_F64,
                             z: y as f64 * GRID_SIZE,
                         };
                         // for child in children {
@@ -423,7 +526,7 @@ pub fn update_ground(
                                 Visibility::Visible,
                                 Transform::from_translation(Vec3::new(
                                     x as f32 * GRID_SIZE as f32,
-                                    min_height,
+                                    min_height - FIELD_ELEVATION_F32,
                                     y as f32 * GRID_SIZE as f32,
                                 )),
                                 Mesh3d(meshes.add(create_height_quad_mesh(
@@ -433,7 +536,7 @@ pub fn update_ground(
                                 GroundChunk(x, y),
                                 GlobalPosition {
                                     x: x as f64 * GRID_SIZE,
-                                    y: min_height as f64,
+                                    y: min_height as f64 - FIELD_ELEVATION_F64,
                                     z: y as f64 * GRID_SIZE,
                                 },
                             ))
@@ -515,21 +618,15 @@ fn create_height_quad_mesh(
     .with_inserted_indices(Indices::U32(indices))
 }
 
-pub fn find(lat: f64, lon: f64, meta: &Res<GroundMeta>, data: &[f32]) -> Result<f32, ()> {
-    let lats = &meta.lats;
-    let lons = &meta.lons;
+// tiles are checked when they are decoded, lookups clamp to the tile
+pub fn find(lat: f64, lon: f64, tile: &GroundTile) -> f32 {
+    let lats = &tile.lats;
+    let lons = &tile.lons;
+    let data = &tile.height;
 
     let n_lat = lats.len();
     let n_lon = lons.len();
 
-    if n_lat < 2 || n_lon < 2 {
-        return Err(()); // not enough data to interpolate
-    }
-
-    if data.len() != n_lat * n_lon {
-        return Err(()); // mismatched data grid
-    }
-
     // --- Clamp lat/lon to grid range ---
     let lat: f64 = lat.clamp(lats[0], lats[n_lat - 1]);
     let lon: f64 = lon.clamp(lons[0], lons[n_lon - 1]);
@@ -569,26 +666,16 @@ pub fn find(lat: f64, lon: f64, meta: &Res<GroundMeta>, data: &[f32]) -> Result<
     // --- Bilinear interpolation ---
     let f0: f32 = f00 * (1.0 - t as f32) + f10 * t as f32;
     let f1: f32 = f01 * (1.0 - t as f32) + f11 * t as f32;
-    let value: f32 = f0 * (1.0 - u as f32) + f1 * u as f32;
 
-    Ok(value)
+    f0 * (1.0 - u as f32) + f1 * u as f32
 }
 
-pub fn find_nearest_land_cover(
-    lat: f64,
-    lon: f64,
-    meta: &GroundMeta,
-    data: &[LandCover],
-) -> Result<LandCover, ()> {
-    let lats = &meta.lats;
-    let lons = &meta.lons;
+pub fn find_nearest_land_cover(lat: f64, lon: f64, tile: &GroundTile) -> LandCover {
+    let lats = &tile.lats;
+    let lons = &tile.lons;
     let n_lat = lats.len();
     let n_lon = lons.len();
 
-    if n_lat == 0 || n_lon == 0 || data.len() != n_lat * n_lon {
-        return Err(()); // Invalid grid
-    }
-
     // Clamp within bounds
     let lat = lat.clamp(lats[0], lats[n_lat - 1]);
     let lon = lon.clamp(lons[0], lons[n_lon - 1]);
@@ -628,5 +715,5 @@ pub fn find_nearest_land_cover(
     // Flatten 2D index into 1D
     let idx = lat_idx * n_lon + lon_idx;
 
-    Ok(data[idx])
+    tile.land_use[idx]
 }
@@ -4,7 +4,7 @@ use std::{
 };
 
 use bevy::{
-    app::{FixedUpdate, Plugin, Startup},
+    app::{Plugin, PostUpdate, Startup},
     asset::{AssetServer, Assets},
     camera::{Camera, ClearColor},
     color::{Color, Srgba},
@@ -12,6 +12,7 @@ use bevy::{
         entity::Entity,
         query::{With, Without},
         resource::Resource,
+        schedule::IntoScheduleConfigs,
         system::{Commands, Query, Res, ResMut, Single},
     },
     light::{AmbientLight, DirectionalLight},
@@ -19,20 +20,25 @@ use bevy::{
     mesh::{Mesh, Mesh3d},
     pbr::{MeshMaterial3d, StandardMaterial},
     prelude::Component,
-    transform::components::{GlobalTransform, Transform},
+    transform::{
+        TransformSystems,
+        components::{GlobalTransform, Transform},
+    },
     utils::default,
 };
 
 use crate::{
     player::Player,
+    projectile::interpolation::{Interpolated, InterpolationSet},
     world::{
         ground::GroundPlugin,
         props::{PropPlugin, SpawnPropsMessage},
     },
 };
 
-mod ground;
+pub mod ground;
 mod props;
+pub mod tiles;
 pub mod util;
 
 #[derive(Resource, Default)]
@@ -99,28 +105,43 @@ impl Plugin for WorldPlugin {
             .insert_resource(ClearColor(Color::srgb(0.02, 0.02, 0.08)))
             .init_resource::<MovingOrigin>()
             .add_systems(Startup, (setup_world, sky_box_follow_camera))
-            .add_systems(FixedUpdate, moving_origin);
+            .add_systems(
+                PostUpdate,
+                moving_origin
+                    .after(InterpolationSet)
+                    .before(TransformSystems::Propagate),
+            );
     }
 }
 
+// runs on the interpolated positions so the world moves as smoothly as the plane
 fn moving_origin(
     center: Res<MovingOrigin>,
-    query: Query<(Entity, &mut Transform, &GlobalPosition)>,
+    query: Query<(Entity, &mut Transform, &GlobalPosition, Option<&Interpolated>)>,
 ) {
     let Some(center_entity) = center.0 else {
         return;
     };
 
+    let render_position = |position: &GlobalPosition, interpolated: Option<&Interpolated>| {
+        match interpolated.and_then(Interpolated::render) {
+            Some(render) => render.position,
+            None => *position,
+        }
+    };
+
     let center = {
-        let Ok((_, _, center)) = query.get(center_entity) else {
+        let Ok((_, _, center, interpolated)) = query.get(center_entity) else {
             panic!("Invalid state");
             // return;
         };
 
-        center.clone()
+        render_position(center, interpolated)
     };
 
-    for (entity, mut transform, global_position) in query {
+    for (entity, mut transform, global_position, interpolated) in query {
+        let global_position = render_position(global_position, interpolated);
+
         let new_translation: Vec3 = Vec3 {
             x: (global_position.x - center.x) as f32,
             y: (global_position.y - center.y) as f32,
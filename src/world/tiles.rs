@@ -0,0 +1,473 @@
+// shared with the tile_convert tool, keep this free of game code
+//
+// versioned container for the .ground and .weather files, all little endian:
+//   header  magic, version, kind, source extent, tile size, tile count, extra length
+//   index   tile lat/lon index, byte offset from the start of the file, byte length
+//   extra   kind specific metadata
+//   tiles   payloads, each one readable on its own
+use thiserror::Error;
+
+pub const MAGIC: [u8; 4] = *b"RMTL";
+pub const VERSION: u16 = 1;
+
+pub const HEADER_LEN: usize = 4 + 2 + 2 + 5 * 8 + 4 + 4;
+pub const ENTRY_LEN: usize = 4 + 4 + 8 + 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TileKind {
+    Ground = 1,
+    Weather = 2,
+}
+
+#[derive(Debug, Error)]
+pub enum TileError {
+    #[error("Not a tiled file, convert it with tile_convert")]
+    BadMagic,
+
+    #[error("Unsupported tile format version {0}, expected {VERSION}")]
+    UnsupportedVersion(u16),
+
+    #[error("Expected {expected:?} tiles, found kind {found}")]
+    WrongKind { expected: TileKind, found: u16 },
+
+    #[error("Unexpected end of tile data")]
+    Truncated,
+
+    #[error("Invalid tile data: {0}")]
+    Invalid(&'static str),
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct TileEntry {
+    pub tile: (i32, i32), // lat, lon
+    pub offset: u64,
+    pub len: u32,
+}
+
+// tiles are tile_size degrees square, counted from the lower corner of the source extent
+#[derive(Debug, Clone)]
+pub struct TileHeader {
+    pub kind: TileKind,
+    pub lat_range: (f64, f64),
+    pub lon_range: (f64, f64),
+    pub tile_size: f64,          // degrees
+    pub entries: Vec<TileEntry>, // sorted by tile
+    pub extra: Vec<u8>,
+}
+
+impl TileHeader {
+    // the fixed part, returns the header with a blank index and how many bytes the rest takes
+    pub fn decode_fixed(bytes: &[u8], expected: TileKind) -> Result<(Self, usize), TileError> {
+        let mut reader = ByteReader::new(bytes);
+
+        if reader.bytes(4)? != MAGIC {
+            return Err(TileError::BadMagic);
+        }
+
+        let version: u16 = reader.u16()?;
+        if version != VERSION {
+            return Err(TileError::UnsupportedVersion(version));
+        }
+
+        let found: u16 = reader.u16()?;
+        if found != expected as u16 {
+            return Err(TileError::WrongKind { expected, found });
+        }
+
+        let lat_range = (reader.f64()?, reader.f64()?);
+        let lon_range = (reader.f64()?, reader.f64()?);
+        let tile_size: f64 = reader.f64()?;
+
+        if tile_size.is_nan()
+            || tile_size <= 0.
+            || lat_range.0 > lat_range.1
+            || lon_range.0 > lon_range.1
+        {
+            return Err(TileError::Invalid("bad extent"));
+        }
+
+        let n_tiles: usize = reader.u32()? as usize;
+        let extra_len: usize = reader.u32()? as usize;
+
+        let header = TileHeader {
+            kind: expected,
+            lat_range,
+            lon_range,
+            tile_size,
+            entries: vec![
+                TileEntry {
+                    tile: (0, 0),
+                    offset: 0,
+                    len: 0,
+                };
+                n_tiles
+            ],
+            extra: vec![0; extra_len],
+        };
+
+        Ok((header, n_tiles * ENTRY_LEN + extra_len))
+    }
+
+    // the index and extra data following the fixed part
+    pub fn decode_rest(&mut self, bytes: &[u8]) -> Result<(), TileError> {
+        let mut reader = ByteReader::new(bytes);
+
+        for entry in &mut self.entries {
+            *entry = TileEntry {
+                tile: (reader.i32()?, reader.i32()?),
+                offset: reader.u64()?,
+                len: reader.u32()?,
+            };
+        }
+
+        if !self
+            .entries
+            .windows(2)
+            .all(|pair| pair[0].tile < pair[1].tile)
+        {
+            return Err(TileError::Invalid("unsorted index"));
+        }
+
+        let extra_len: usize = self.extra.len();
+        self.extra = reader.bytes(extra_len)?.to_vec();
+
+        Ok(())
+    }
+
+    pub fn decode(bytes: &[u8], expected: TileKind) -> Result<Self, TileError> {
+        let (mut header, rest) = Self::decode_fixed(bytes, expected)?;
+
+        let end: usize = HEADER_LEN + rest;
+        header.decode_rest(bytes.get(HEADER_LEN..end).ok_or(TileError::Truncated)?)?;
+
+        Ok(header)
+    }
+
+    pub fn tile_count(&self) -> (i32, i32) {
+        let count = |(min, max): (f64, f64)| (((max - min) / self.tile_size).ceil() as i32).max(1);
+
+        (count(self.lat_range), count(self.lon_range))
+    }
+
+    // positions outside the extent fall on the nearest edge tile
+    pub fn tile_of(&self, lat: f64, lon: f64) -> (i32, i32) {
+        let (n_lat, n_lon) = self.tile_count();
+        let index = |value: f64, (min, max): (f64, f64), n: i32| {
+            (((value.clamp(min, max) - min) / self.tile_size).floor() as i32).clamp(0, n - 1)
+        };
+
+        (
+            index(lat, self.lat_range, n_lat),
+            index(lon, self.lon_range, n_lon),
+        )
+    }
+
+    pub fn entry(&self, tile: (i32, i32)) -> Option<&TileEntry> {
+        self.entries
+            .binary_search_by_key(&tile, |entry| entry.tile)
+            .ok()
+            .map(|i| &self.entries[i])
+    }
+
+    // the payload of a tile in a fully read file
+    pub fn payload<'a>(&self, bytes: &'a [u8], entry: &TileEntry) -> Result<&'a [u8], TileError> {
+        let start: usize = entry.offset as usize;
+
+        bytes
+            .get(start..start + entry.len as usize)
+            .ok_or(TileError::Truncated)
+    }
+}
+
+// only the tile_convert tool writes tiles
+#[allow(dead_code)]
+impl TileHeader {
+    // header, index and extra data followed by the payloads, fills in the index offsets
+    pub fn encode(mut self, mut tiles: Vec<((i32, i32), Vec<u8>)>) -> Vec<u8> {
+        tiles.sort_by_key(|(tile, _)| *tile);
+
+        let mut offset: u64 = (HEADER_LEN + tiles.len() * ENTRY_LEN + self.extra.len()) as u64;
+        self.entries = tiles
+            .iter()
+            .map(|(tile, payload)| {
+                let entry = TileEntry {
+                    tile: *tile,
+                    offset,
+                    len: payload.len() as u32,
+                };
+                offset += payload.len() as u64;
+                entry
+            })
+            .collect();
+
+        let mut writer = ByteWriter::default();
+        writer.bytes(&MAGIC);
+        writer.u16(VERSION);
+        writer.u16(self.kind as u16);
+        writer.f64(self.lat_range.0);
+        writer.f64(self.lat_range.1);
+        writer.f64(self.lon_range.0);
+        writer.f64(self.lon_range.1);
+        writer.f64(self.tile_size);
+        writer.u32(self.entries.len() as u32);
+        writer.u32(self.extra.len() as u32);
+
+        for entry in &self.entries {
+            writer.i32(entry.tile.0);
+            writer.i32(entry.tile.1);
+            writer.u64(entry.offset);
+            writer.u32(entry.len);
+        }
+
+        writer.bytes(&self.extra);
+        for (_, payload) in &tiles {
+            writer.bytes(payload);
+        }
+
+        writer.0
+    }
+}
+
+// --- Byte helpers ---
+
+pub struct ByteReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, pos: 0 }
+    }
+
+    pub fn bytes(&mut self, len: usize) -> Result<&'a [u8], TileError> {
+        let slice: &[u8] = self
+            .bytes
+            .get(self.pos..self.pos + len)
+            .ok_or(TileError::Truncated)?;
+        self.pos += len;
+
+        Ok(slice)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], TileError> {
+        Ok(self.bytes(N)?.try_into().unwrap())
+    }
+
+    pub fn u16(&mut self) -> Result<u16, TileError> {
+        Ok(u16::from_le_bytes(self.array()?))
+    }
+
+    pub fn u32(&mut self) -> Result<u32, TileError> {
+        Ok(u32::from_le_bytes(self.array()?))
+    }
+
+    pub fn i32(&mut self) -> Result<i32, TileError> {
+        Ok(i32::from_le_bytes(self.array()?))
+    }
+
+    pub fn u64(&mut self) -> Result<u64, TileError> {
+        Ok(u64::from_le_bytes(self.array()?))
+    }
+
+    pub fn f64(&mut self) -> Result<f64, TileError> {
+        Ok(f64::from_le_bytes(self.array()?))
+    }
+
+    // a u32 length followed by the values
+    pub fn f32s(&mut self) -> Result<Vec<f32>, TileError> {
+        let len: usize = self.u32()? as usize;
+        self.bytes(len * 4)?
+            .chunks_exact(4)
+            .map(|chunk| Ok(f32::from_le_bytes(chunk.try_into().unwrap())))
+            .collect()
+    }
+
+    pub fn f64s(&mut self) -> Result<Vec<f64>, TileError> {
+        let len: usize = self.u32()? as usize;
+        self.bytes(len * 8)?
+            .chunks_exact(8)
+            .map(|chunk| Ok(f64::from_le_bytes(chunk.try_into().unwrap())))
+            .collect()
+    }
+
+    pub fn u16s(&mut self) -> Result<Vec<u16>, TileError> {
+        let len: usize = self.u32()? as usize;
+        self.bytes(len * 2)?
+            .chunks_exact(2)
+            .map(|chunk| Ok(u16::from_le_bytes(chunk.try_into().unwrap())))
+            .collect()
+    }
+}
+
+#[derive(Default)]
+#[allow(dead_code)]
+pub struct ByteWriter(pub Vec<u8>);
+
+#[allow(dead_code)]
+impl ByteWriter {
+    pub fn bytes(&mut self, bytes: &[u8]) {
+        self.0.extend_from_slice(bytes);
+    }
+
+    pub fn u16(&mut self, value: u16) {
+        self.bytes(&value.to_le_bytes());
+    }
+
+    pub fn u32(&mut self, value: u32) {
+        self.bytes(&value.to_le_bytes());
+    }
+
+    pub fn i32(&mut self, value: i32) {
+        self.bytes(&value.to_le_bytes());
+    }
+
+    pub fn u64(&mut self, value: u64) {
+        self.bytes(&value.to_le_bytes());
+    }
+
+    pub fn f64(&mut self, value: f64) {
+        self.bytes(&value.to_le_bytes());
+    }
+
+    pub fn f32s(&mut self, values: &[f32]) {
+        self.u32(values.len() as u32);
+        values
+            .iter()
+            .for_each(|value| self.bytes(&value.to_le_bytes()));
+    }
+
+    pub fn f64s(&mut self, values: &[f64]) {
+        self.u32(values.len() as u32);
+        values
+            .iter()
+            .for_each(|value| self.bytes(&value.to_le_bytes()));
+    }
+
+    pub fn u16s(&mut self, values: &[u16]) {
+        self.u32(values.len() as u32);
+        values
+            .iter()
+            .for_each(|value| self.bytes(&value.to_le_bytes()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header() -> TileHeader {
+        TileHeader {
+            kind: TileKind::Ground,
+            lat_range: (45.0, 47.5),
+            lon_range: (5.0, 8.0),
+            tile_size: 1.0,
+            entries: Vec::new(),
+            extra: vec![1, 2, 3],
+        }
+    }
+
+    fn tiles() -> Vec<((i32, i32), Vec<u8>)> {
+        // out of order on purpose, encode sorts the index
+        vec![
+            ((2, 0), vec![20, 21]),
+            ((0, 1), vec![1]),
+            ((0, 0), vec![0, 0, 0, 0]),
+            ((1, 2), Vec::new()),
+        ]
+    }
+
+    #[test]
+    fn header_round_trip() {
+        let bytes: Vec<u8> = header().encode(tiles());
+        let decoded = TileHeader::decode(&bytes, TileKind::Ground).unwrap();
+
+        assert_eq!(decoded.kind, TileKind::Ground);
+        assert_eq!(decoded.lat_range, (45.0, 47.5));
+        assert_eq!(decoded.lon_range, (5.0, 8.0));
+        assert_eq!(decoded.tile_size, 1.0);
+        assert_eq!(decoded.extra, vec![1, 2, 3]);
+        assert_eq!(decoded.entries.len(), 4);
+
+        for (tile, payload) in tiles() {
+            let entry = decoded.entry(tile).unwrap();
+            assert_eq!(decoded.payload(&bytes, entry).unwrap(), &payload[..]);
+        }
+        assert!(decoded.entry((1, 1)).is_none());
+    }
+
+    #[test]
+    fn header_split_read() {
+        let bytes: Vec<u8> = header().encode(tiles());
+
+        let (mut decoded, rest) =
+            TileHeader::decode_fixed(&bytes[..HEADER_LEN], TileKind::Ground).unwrap();
+        assert_eq!(rest, 4 * ENTRY_LEN + 3);
+
+        decoded
+            .decode_rest(&bytes[HEADER_LEN..HEADER_LEN + rest])
+            .unwrap();
+        assert_eq!(decoded.entry((2, 0)).unwrap().len, 2);
+    }
+
+    #[test]
+    fn header_errors() {
+        let bytes: Vec<u8> = header().encode(tiles());
+
+        assert!(matches!(
+            TileHeader::decode(&bytes, TileKind::Weather),
+            Err(TileError::WrongKind { found: 1, .. })
+        ));
+        assert!(matches!(
+            TileHeader::decode(&bytes[..HEADER_LEN + 10], TileKind::Ground),
+            Err(TileError::Truncated)
+        ));
+        assert!(matches!(
+            TileHeader::decode(b"RON(", TileKind::Ground),
+            Err(TileError::BadMagic)
+        ));
+
+        let mut future: Vec<u8> = bytes.clone();
+        future[4..6].copy_from_slice(&(VERSION + 1).to_le_bytes());
+        assert!(matches!(
+            TileHeader::decode(&future, TileKind::Ground),
+            Err(TileError::UnsupportedVersion(_))
+        ));
+
+        let decoded = TileHeader::decode(&bytes, TileKind::Ground).unwrap();
+        let entry = decoded.entry((2, 0)).unwrap();
+        assert!(matches!(
+            decoded.payload(&bytes[..bytes.len() - 1], entry),
+            Err(TileError::Truncated)
+        ));
+    }
+
+    #[test]
+    fn tile_of_clamps_to_the_extent() {
+        let header = header();
+
+        assert_eq!(header.tile_count(), (3, 3));
+        assert_eq!(header.tile_of(45.0, 5.0), (0, 0));
+        assert_eq!(header.tile_of(46.5, 7.2), (1, 2));
+        assert_eq!(header.tile_of(47.5, 8.0), (2, 2));
+        assert_eq!(header.tile_of(10.0, 100.0), (0, 2));
+    }
+
+    #[test]
+    fn bytes_round_trip() {
+        let mut writer = ByteWriter::default();
+        writer.u16(7);
+        writer.i32(-3);
+        writer.f32s(&[1.5, -2.25]);
+        writer.f64s(&[std::f64::consts::PI]);
+        writer.u16s(&[1, 2, 3]);
+
+        let mut reader = ByteReader::new(&writer.0);
+        assert_eq!(reader.u16().unwrap(), 7);
+        assert_eq!(reader.i32().unwrap(), -3);
+        assert_eq!(reader.f32s().unwrap(), vec![1.5, -2.25]);
+        assert_eq!(reader.f64s().unwrap(), vec![std::f64::consts::PI]);
+        assert_eq!(reader.u16s().unwrap(), vec![1, 2, 3]);
+        assert!(matches!(reader.u32(), Err(TileError::Truncated)));
+    }
+}
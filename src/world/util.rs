@@ -1,3 +1,7 @@
+// MSL elevation of world y = 0 (Lahr)
+pub const FIELD_ELEVATION_F32: f32 = 156.;
+pub const FIELD_ELEVATION_F64: f64 = 156.;
+
 const LAHR_LAT_F32: f32 = 48.123;
 const LAHR_LON_F32: f32 = 7.873;
 